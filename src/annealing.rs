@@ -0,0 +1,153 @@
+use std::time::{Duration, Instant};
+
+use crate::{Rectangle, Position};
+use crate::rng::Rng;
+
+/// 配置関数の型。`skyline_placement` や `maxrects_placement` などをそのまま渡せる
+pub type PlacementFn = fn(&[Rectangle], u32, u32, bool) -> Vec<(Rectangle, Position, bool)>;
+
+/// 焼きなまし法の設定
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnnealingConfig {
+    pub seed: u64,
+    /// 反復回数の上限（`time_limit` と併用した場合は先に達した方で終了）。
+    /// どちらも指定しない場合は貪欲配置の結果をそのまま返す
+    pub max_iterations: Option<u64>,
+    pub time_limit: Option<Duration>,
+    pub initial_temperature: f64,
+    /// 1反復ごとに温度に掛ける係数
+    pub cooling_rate: f64,
+    pub allow_rotate: bool,
+}
+
+impl Default for AnnealingConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            max_iterations: Some(1000),
+            time_limit: None,
+            initial_temperature: 0.1,
+            cooling_rate: 0.995,
+            allow_rotate: true,
+        }
+    }
+}
+
+/// 配置順と向きの組（解の表現）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Sequence {
+    pub(crate) order: Vec<usize>,
+    pub(crate) rotated: Vec<bool>,
+}
+
+impl Sequence {
+    pub(crate) fn identity(n: usize) -> Self {
+        Self { order: (0..n).collect(), rotated: vec![false; n] }
+    }
+
+    /// 配置関数をデコーダとして使い、元の矩形と向きで結果を返す
+    pub(crate) fn decode(&self, rectangles: &[Rectangle], container_width: u32, container_height: u32, decoder: PlacementFn) -> Vec<(Rectangle, Position, bool)> {
+        let oriented: Vec<Rectangle> = self
+            .order
+            .iter()
+            .map(|&i| {
+                let r = rectangles[i];
+                if self.rotated[i] { Rectangle { width: r.height, height: r.width } } else { r }
+            })
+            .collect();
+        let placed = decoder(&oriented, container_width, container_height, false);
+        let mut used = vec![false; oriented.len()];
+        placed
+            .into_iter()
+            .filter_map(|(rect, pos, decoder_rotated)| {
+                let k = (0..oriented.len()).find(|&k| !used[k] && oriented[k] == rect)?;
+                used[k] = true;
+                let i = self.order[k];
+                Some((rectangles[i], pos, self.rotated[i] != decoder_rotated))
+            })
+            .collect()
+    }
+}
+
+/// 配置結果の良さ。配置面積が大きいほど、次に外接矩形が小さいほど良い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Fitness {
+    pub(crate) placed_area: u64,
+    pub(crate) neg_bounding_area: i64,
+}
+
+impl Fitness {
+    pub(crate) fn of(placed: &[(Rectangle, Position, bool)]) -> Self {
+        let placed_area = placed.iter().map(|(r, _, _)| r.width as u64 * r.height as u64).sum();
+        let (mut max_x, mut max_y) = (0u64, 0u64);
+        for &(r, p, rot) in placed {
+            let (w, h) = if rot { (r.height, r.width) } else { (r.width, r.height) };
+            max_x = max_x.max((p.x + w) as u64);
+            max_y = max_y.max((p.y + h) as u64);
+        }
+        Self { placed_area, neg_bounding_area: -((max_x * max_y) as i64) }
+    }
+
+    /// 焼きなましの受理判定に使うエネルギー（小さいほど良い）
+    pub(crate) fn energy(&self, container_area: f64) -> f64 {
+        let unplaced = 1.0 - self.placed_area as f64 / container_area;
+        let bounding = -self.neg_bounding_area as f64 / container_area;
+        unplaced + bounding * 1e-3
+    }
+}
+
+/// 配置順と向きを焼きなまし法で改善する関数。
+/// 与えられた順序での貪欲配置より悪い結果は返さない
+pub fn anneal_placement(rectangles: &[Rectangle], container_width: u32, container_height: u32, decoder: PlacementFn, config: &AnnealingConfig) -> Vec<(Rectangle, Position, bool)> {
+    let greedy = decoder(rectangles, container_width, container_height, config.allow_rotate);
+    let mut best_fitness = Fitness::of(&greedy);
+    let mut best = greedy;
+    let n = rectangles.len();
+    if n == 0 {
+        return best;
+    }
+
+    let container_area = (container_width as f64 * container_height as f64).max(1.0);
+    let mut rng = Rng::new(config.seed);
+    let mut current = Sequence::identity(n);
+    let mut current_fitness = Fitness::of(&current.decode(rectangles, container_width, container_height, decoder));
+    let mut temperature = config.initial_temperature;
+    let start = Instant::now();
+    let mut iteration = 0u64;
+
+    loop {
+        if config.max_iterations.is_some_and(|max| iteration >= max)
+            || config.time_limit.is_some_and(|limit| start.elapsed() >= limit)
+            || (config.max_iterations.is_none() && config.time_limit.is_none())
+        {
+            break;
+        }
+        iteration += 1;
+
+        // 近傍: 2要素の入れ替えか、1要素の回転
+        let mut candidate = current.clone();
+        let i = rng.below(n);
+        let rotatable = config.allow_rotate && rectangles[candidate.order[i]].width != rectangles[candidate.order[i]].height;
+        if rotatable && rng.below(2) == 0 {
+            let idx = candidate.order[i];
+            candidate.rotated[idx] = !candidate.rotated[idx];
+        } else {
+            let j = rng.below(n);
+            candidate.order.swap(i, j);
+        }
+
+        let placed = candidate.decode(rectangles, container_width, container_height, decoder);
+        let fitness = Fitness::of(&placed);
+        let delta = fitness.energy(container_area) - current_fitness.energy(container_area);
+        if delta <= 0.0 || rng.next_f64() < (-delta / temperature.max(1e-12)).exp() {
+            current = candidate;
+            current_fitness = fitness;
+        }
+        if fitness > best_fitness {
+            best_fitness = fitness;
+            best = placed;
+        }
+        temperature *= config.cooling_rate;
+    }
+    best
+}
@@ -0,0 +1,66 @@
+use std::cmp::Reverse;
+
+use crate::{Rectangle, Position};
+use crate::skyline::Skyline;
+
+/// ビームサーチの部分配置
+#[derive(Clone)]
+struct BeamState {
+    skyline: Skyline,
+    placed: Vec<(Rectangle, Position, bool)>,
+    placed_area: u64,
+}
+
+impl BeamState {
+    /// 評価値（小さいほど良い）: 配置面積、無駄になった空き面積、最大高さの順
+    fn score(&self) -> (Reverse<u64>, u64, u32) {
+        let wasted = self.skyline.area_below() - self.placed_area;
+        (Reverse(self.placed_area), wasted, self.skyline.max_height())
+    }
+}
+
+/// Skyline法を基にしたビームサーチによる配置関数。
+/// 各段階で上位 `beam_width` 個の部分配置を保持し、幅1ならSkyline法と同じ結果になる。
+/// 幅を広げてもSkyline法より悪い結果は返さない
+pub fn beam_search_placement(rectangles: &[Rectangle], container_width: u32, container_height: u32, allow_rotate: bool, beam_width: usize) -> Vec<(Rectangle, Position, bool)> {
+    let best = search(rectangles, container_width, container_height, allow_rotate, beam_width.max(1));
+    if beam_width <= 1 {
+        return best.placed;
+    }
+    // 枝刈りで貪欲法の経路が落ちることがあるので、貪欲法の結果と比べる
+    let greedy = search(rectangles, container_width, container_height, allow_rotate, 1);
+    if greedy.score() < best.score() { greedy.placed } else { best.placed }
+}
+
+fn search(rectangles: &[Rectangle], container_width: u32, container_height: u32, allow_rotate: bool, beam_width: usize) -> BeamState {
+    let mut beam = vec![BeamState {
+        skyline: Skyline::new(container_width, Some(container_height)),
+        placed: Vec::new(),
+        placed_area: 0,
+    }];
+
+    for &rect in rectangles {
+        let mut children: Vec<BeamState> = Vec::new();
+        for state in &beam {
+            let candidates = state.skyline.candidates(rect, allow_rotate);
+            if candidates.is_empty() {
+                // 配置できない矩形は飛ばす
+                children.push(state.clone());
+                continue;
+            }
+            // 各状態からは貪欲法の優先順で上位の候補だけを展開する
+            for candidate in candidates.iter().take(beam_width) {
+                let mut child = state.clone();
+                child.skyline.place(candidate);
+                child.placed.push((rect, Position { x: candidate.x, y: candidate.y }, candidate.rotated));
+                child.placed_area += rect.width as u64 * rect.height as u64;
+                children.push(child);
+            }
+        }
+        // 同点なら展開順（貪欲法に近い方）を優先する
+        children.sort_by_key(|s| s.score());
+        children.truncate(beam_width);
+        beam = children;
+    }
+    beam.into_iter().next().unwrap()
+}
@@ -0,0 +1,236 @@
+use crate::{Rectangle, Position, PackAlgorithm};
+
+/// ビンサイズ探索の制約
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BinSizeConstraints {
+    /// 幅と高さを2のべき乗に限る
+    pub power_of_two: bool,
+    /// 正方形に限る
+    pub square: bool,
+    /// 幅と高さの上限
+    pub max_side: Option<u32>,
+    /// 長辺/短辺の上限
+    pub max_aspect_ratio: Option<f64>,
+}
+
+/// ビンサイズ探索の結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinSizeResult {
+    pub width: u32,
+    pub height: u32,
+    pub placements: Vec<(Rectangle, Position, bool)>,
+}
+
+impl BinSizeConstraints {
+    fn accepts(&self, width: u32, height: u32) -> bool {
+        if self.square && width != height {
+            return false;
+        }
+        if self.power_of_two && !(width.is_power_of_two() && height.is_power_of_two()) {
+            return false;
+        }
+        if let Some(max_side) = self.max_side {
+            if width > max_side || height > max_side {
+                return false;
+            }
+        }
+        if let Some(ratio) = self.max_aspect_ratio {
+            if width.max(height) as f64 > width.min(height) as f64 * ratio {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// すべての矩形が入る最小面積のビンを探索する関数
+pub fn search_bin_size(rectangles: &[Rectangle], algorithm: PackAlgorithm, allow_rotate: bool, constraints: &BinSizeConstraints) -> Option<BinSizeResult> {
+    let fits = |w: u32, h: u32| {
+        let placed = algorithm.place(rectangles, w, h, allow_rotate);
+        (placed.len() == rectangles.len()).then_some(placed)
+    };
+    let (width, height, placements) = search_size(rectangles, allow_rotate, 0, constraints, fits)?;
+    Some(BinSizeResult { width, height, placements })
+}
+
+/// 縦横比の上限を指定しない場合にまず試す上限（矩形がより細長い場合はそれに合わせる）
+const DEFAULT_ASPECT_RATIO: f64 = 4.0;
+/// 幅の候補がこの数以下なら全て試す
+const EXHAUSTIVE_WIDTHS: u32 = 64;
+/// 粗い探索で幅を増やす倍率
+const COARSE_FACTOR: f64 = 1.1;
+
+/// `fits` がSomeを返す最小面積のビンを探索する。
+/// `slack` は上限の辺の長さに加える余裕（kerfなど）
+pub(crate) fn search_size<T>(rectangles: &[Rectangle], allow_rotate: bool, slack: u32, constraints: &BinSizeConstraints, fits: impl Fn(u32, u32) -> Option<T>) -> Option<(u32, u32, T)> {
+    if rectangles.is_empty() {
+        return None;
+    }
+    if constraints.max_aspect_ratio.is_some() || constraints.square {
+        return search_within(rectangles, allow_rotate, slack, constraints, &fits);
+    }
+    // 極端に細長いビンを避けるため、まず既定の縦横比の範囲で探す
+    let (min_width, min_height) = min_sides(rectangles, allow_rotate);
+    let ratio = DEFAULT_ASPECT_RATIO.max(min_width.max(min_height) as f64 / min_width.min(min_height) as f64);
+    let limited = BinSizeConstraints { max_aspect_ratio: Some(ratio), ..*constraints };
+    search_within(rectangles, allow_rotate, slack, &limited, &fits)
+        .or_else(|| search_within(rectangles, allow_rotate, slack, constraints, &fits))
+}
+
+/// 各矩形が少なくとも入る幅と高さ
+fn min_sides(rectangles: &[Rectangle], allow_rotate: bool) -> (u32, u32) {
+    let min_width = rectangles.iter().map(|r| if allow_rotate { r.width.min(r.height) } else { r.width }).max().unwrap_or(0).max(1);
+    let min_height = rectangles.iter().map(|r| if allow_rotate { r.width.min(r.height) } else { r.height }).max().unwrap_or(0).max(1);
+    (min_width, min_height)
+}
+
+fn search_within<T>(rectangles: &[Rectangle], allow_rotate: bool, slack: u32, constraints: &BinSizeConstraints, fits: &impl Fn(u32, u32) -> Option<T>) -> Option<(u32, u32, T)> {
+    let total_area: u64 = rectangles.iter().map(|r| r.width as u64 * r.height as u64).sum();
+    let (min_width, min_height) = min_sides(rectangles, allow_rotate);
+    // 上限を指定しない場合は全矩形を1列に並べられる長さまで探索する
+    let sum_side: u64 = rectangles.iter().map(|r| r.width.max(r.height) as u64).sum();
+    let upper = constraints.max_side.unwrap_or((sum_side + slack as u64).min(u32::MAX as u64) as u32);
+    let mut w_lo = min_width;
+    if let Some(ratio) = constraints.max_aspect_ratio {
+        // 高さは幅×比率以下なので、面積を満たすには幅が√(面積/比率)以上必要
+        w_lo = w_lo.max((total_area as f64 / ratio).sqrt().floor() as u32);
+    }
+    if w_lo > upper {
+        return None;
+    }
+
+    let mut search = Search { total_area, min_height, upper, constraints, fits, best: None };
+    if constraints.power_of_two {
+        for w in (0..32).map(|i| 1u32 << i).filter(|&w| w >= w_lo && w <= upper) {
+            if search.pruned(w) {
+                break;
+            }
+            search.try_width(w);
+        }
+    } else if upper - w_lo < EXHAUSTIVE_WIDTHS {
+        for w in w_lo..=upper {
+            if search.pruned(w) {
+                break;
+            }
+            search.try_width(w);
+        }
+    } else {
+        // 幅を倍率で増やしながら粗く探し、最良の幅の周りを刻みを半分にしながら詰める
+        let mut w = w_lo;
+        let mut coarse = Vec::new();
+        while w <= upper && !search.pruned(w) {
+            coarse.push(w);
+            search.try_width(w);
+            let next = ((w as f64 * COARSE_FACTOR).ceil() as u64).max(w as u64 + 1);
+            w = next.min(u32::MAX as u64) as u32;
+            if next > upper as u64 {
+                break;
+            }
+        }
+        let (best_w, _, _) = search.best.as_ref()?;
+        let best_w = *best_w;
+        let i = coarse.iter().position(|&c| c == best_w).unwrap_or(0);
+        let mut step = (coarse.get(i + 1).copied().unwrap_or(best_w) - coarse[i.saturating_sub(1)]).div_ceil(2);
+        let mut center = best_w;
+        while step >= 1 {
+            for w in [center.saturating_sub(step), center.saturating_add(step)] {
+                if w >= w_lo && w <= upper && !search.pruned(w) {
+                    search.try_width(w);
+                }
+            }
+            center = search.best.as_ref().map_or(center, |b| b.0);
+            if step == 1 {
+                break;
+            }
+            step = step.div_ceil(2);
+        }
+    }
+    search.best
+}
+
+/// 幅ごとの高さ探索の状態
+struct Search<'a, T, F: Fn(u32, u32) -> Option<T>> {
+    total_area: u64,
+    min_height: u32,
+    upper: u32,
+    constraints: &'a BinSizeConstraints,
+    fits: &'a F,
+    best: Option<(u32, u32, T)>,
+}
+
+impl<T, F: Fn(u32, u32) -> Option<T>> Search<'_, T, F> {
+    fn is_better(&self, w: u32, h: u32) -> bool {
+        match &self.best {
+            None => true,
+            Some((bw, bh, _)) => {
+                let key = (w as u64 * h as u64, w.max(h), w);
+                key < (*bw as u64 * *bh as u64, *bw.max(bh), *bw)
+            }
+        }
+    }
+
+    /// 幅がこれ以上大きくなると最良解を超える（幅は増やす方向にしか進めない）
+    fn pruned(&self, w: u32) -> bool {
+        let Some((bw, bh, _)) = &self.best else { return false };
+        let best_area = *bw as u64 * *bh as u64;
+        if w as u64 * self.min_height as u64 > best_area {
+            return true;
+        }
+        // 縦横比の上限があれば高さは幅/比率以上
+        self.constraints.max_aspect_ratio.is_some_and(|ratio| w as f64 * (w as f64 / ratio).ceil() > best_area as f64 && w > *bw)
+    }
+
+    fn try_width(&mut self, w: u32) {
+        let constraints = self.constraints;
+        // 面積の下限から高さの探索範囲を決める
+        let mut h_lo = self.min_height.max(self.total_area.div_ceil(w as u64).min(u32::MAX as u64) as u32);
+        let mut h_hi = self.upper;
+        if let Some(ratio) = constraints.max_aspect_ratio {
+            h_lo = h_lo.max((w as f64 / ratio).ceil() as u32);
+            h_hi = h_hi.min((w as f64 * ratio).floor().min(u32::MAX as f64) as u32);
+        }
+        if constraints.square {
+            h_lo = h_lo.max(w);
+            h_hi = h_hi.min(w);
+        }
+        if h_lo > h_hi || !self.is_better(w, h_lo) {
+            return;
+        }
+        if constraints.power_of_two || constraints.square {
+            // 候補が少ないので小さい順に試す
+            let mut h = if constraints.power_of_two { h_lo.next_power_of_two() } else { h_lo };
+            while h <= h_hi && self.is_better(w, h) {
+                if constraints.accepts(w, h) {
+                    if let Some(placements) = (self.fits)(w, h) {
+                        self.best = Some((w, h, placements));
+                        return;
+                    }
+                }
+                if !constraints.power_of_two {
+                    return;
+                }
+                match h.checked_mul(2) {
+                    Some(next) => h = next,
+                    None => return,
+                }
+            }
+        } else {
+            // 高さを二分探索する
+            let Some(mut placements) = (self.fits)(w, h_hi) else { return };
+            let (mut lo, mut hi) = (h_lo, h_hi);
+            while lo < hi {
+                let mid = lo + (hi - lo) / 2;
+                match (self.fits)(w, mid) {
+                    Some(p) => {
+                        hi = mid;
+                        placements = p;
+                    }
+                    None => lo = mid + 1,
+                }
+            }
+            if constraints.accepts(w, hi) && self.is_better(w, hi) {
+                self.best = Some((w, hi, placements));
+            }
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use crate::{Rectangle, Position};
+use std::collections::HashSet;
+
+use crate::{Rectangle, Position, Rotation};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct BLPoint {
@@ -8,17 +10,30 @@ struct BLPoint {
 
 /// BL法によるアイテム配置を行う関数
 pub fn bottom_left_placement(rectangles: &[Rectangle], container_width: u32, container_height: u32, allow_rotate: bool) -> Vec<(Rectangle, Position, bool)> {
+    bottom_left_placement_in(rectangles, container_width, Some(container_height), Rotation::All(allow_rotate))
+}
+
+/// 高さを省略した場合は上方向に制限のないストリップとして配置する
+pub(crate) fn bottom_left_placement_in(rectangles: &[Rectangle], container_width: u32, container_height: Option<u32>, rotation: Rotation) -> Vec<(Rectangle, Position, bool)> {
     let mut placed_rectangles: Vec<(Rectangle, Position, bool)> = Vec::new();
-    for &rect in rectangles {
+    // 空き領域は減るだけなので、一度入らなかったサイズは以降も試さない。
+    // BL点は配置済み矩形の角から作るため、後から増えた角でなら入る場合もまれにあるが、
+    // 同じサイズを大量に並べる場合の速度を優先する
+    let mut failed: HashSet<(u32, u32, bool)> = HashSet::new();
+    for (index, &rect) in rectangles.iter().enumerate() {
+        let key = (rect.width, rect.height, rotation.allows(index));
+        if failed.contains(&key) {
+            continue;
+        }
         let mut candidates = vec![(rect.width, rect.height, false)];
-        if allow_rotate && rect.width != rect.height {
+        if rotation.allows(index) && rect.width != rect.height {
             candidates.push((rect.height, rect.width, true));
         }
         let mut best: Option<(Position, bool)> = None;
         for &(w, h, rotated) in &candidates {
             // 回転時のみ入る場合も考慮する
 
-            if w > container_width || h > container_height {
+            if w > container_width || container_height.is_some_and(|ch| h > ch) {
                 continue;
             }
             let mut bl_points = vec![BLPoint {
@@ -57,7 +72,7 @@ pub fn bottom_left_placement(rectangles: &[Rectangle], container_width: u32, con
             for bl_point in bl_points.iter_mut() {
                 if bl_point.is_valid {
                     let pos = bl_point.position;
-                    if pos.x + w > container_width || pos.y + h > container_height {
+                    if pos.x + w > container_width || container_height.is_some_and(|ch| pos.y + h > ch) {
                         bl_point.is_valid = false;
                         continue;
                     }
@@ -96,8 +111,18 @@ pub fn bottom_left_placement(rectangles: &[Rectangle], container_width: u32, con
                 }
             }
         }
-        if let Some((pos, rotated)) = best {
-            placed_rectangles.push((rect, pos, rotated));
+        // 高さに制限がなければ、配置済みの全矩形の上には必ず置ける
+        if best.is_none() && container_height.is_none() {
+            if let Some(&(_, _, rotated)) = candidates.iter().find(|&&(w, _, _)| w <= container_width) {
+                let top = placed_rectangles.iter().map(|&(r, p, rot)| p.y + if rot { r.width } else { r.height }).max().unwrap_or(0);
+                best = Some((Position { x: 0, y: top }, rotated));
+            }
+        }
+        match best {
+            Some((pos, rotated)) => placed_rectangles.push((rect, pos, rotated)),
+            None => {
+                failed.insert(key);
+            }
         }
     }
     placed_rectangles
@@ -0,0 +1,134 @@
+use crate::Rectangle;
+
+/// 2次元ビンパッキングの必要ビン数の下界
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LowerBounds {
+    /// 面積の合計による下界
+    pub continuous: usize,
+    /// 幅または高さが半分を超える矩形を1次元問題に落とした下界（Martello–Vigo L1）
+    pub l1: usize,
+    /// 大きい矩形の数と残りの面積を組み合わせた下界（Martello–Vigo L2）
+    pub l2: usize,
+}
+
+impl LowerBounds {
+    /// 最も強い下界
+    pub fn best(&self) -> usize {
+        self.continuous.max(self.l1).max(self.l2)
+    }
+}
+
+/// 回転を考慮した向きの一覧
+fn orientations(rect: Rectangle, allow_rotate: bool) -> Vec<(u32, u32)> {
+    let mut result = vec![(rect.width, rect.height)];
+    if allow_rotate && rect.width != rect.height {
+        result.push((rect.height, rect.width));
+    }
+    result
+}
+
+/// 面積の合計による下界
+pub fn continuous_lower_bound(rectangles: &[Rectangle], container_width: u32, container_height: u32) -> usize {
+    let bin_area = container_width as u64 * container_height as u64;
+    let total_area: u64 = rectangles.iter().map(|r| r.width as u64 * r.height as u64).sum();
+    total_area.div_ceil(bin_area.max(1)) as usize
+}
+
+/// 1次元ビンパッキングのMartello–Toth型下界
+fn one_dim_bound(sizes: &[u32], capacity: u32) -> usize {
+    let capacity = capacity as u64;
+    let mut best = (sizes.iter().map(|&s| s as u64).sum::<u64>()).div_ceil(capacity.max(1)) as usize;
+    let mut candidates: Vec<u64> = sizes.iter().map(|&s| s as u64).filter(|&s| s > 0 && 2 * s <= capacity).collect();
+    candidates.push(1);
+    candidates.sort_unstable();
+    candidates.dedup();
+    for p in candidates {
+        // 他の矩形と同じビンに入らないもの、半分を超えるもの、p以上で半分以下のもの
+        let j1 = sizes.iter().filter(|&&s| s as u64 > capacity - p).count();
+        let j2: Vec<u64> = sizes.iter().map(|&s| s as u64).filter(|&s| s <= capacity - p && 2 * s > capacity).collect();
+        let j3: Vec<u64> = sizes.iter().map(|&s| s as u64).filter(|&s| 2 * s <= capacity && s >= p).collect();
+        let j2_free: u64 = j2.iter().map(|&s| capacity - s).sum();
+        let by_size = j3.iter().sum::<u64>().saturating_sub(j2_free).div_ceil(capacity);
+        let j2_slots: u64 = j2.iter().map(|&s| (capacity - s) / p).sum();
+        let by_count = (j3.len() as u64).saturating_sub(j2_slots).div_ceil(capacity / p);
+        best = best.max(j1 + j2.len() + by_size.max(by_count) as usize);
+    }
+    best
+}
+
+/// 幅（または高さ）がどの向きでも半分を超える矩形は横（縦）に並べられないので、
+/// もう一方の辺の最小値で1次元問題に緩和する
+fn l1_bound(rectangles: &[Rectangle], container_width: u32, container_height: u32, allow_rotate: bool) -> usize {
+    let mut tall = Vec::new();
+    let mut wide = Vec::new();
+    for &rect in rectangles {
+        let o = orientations(rect, allow_rotate);
+        if o.iter().all(|&(w, _)| 2 * w as u64 > container_width as u64) {
+            wide.push(o.iter().map(|&(_, h)| h).min().unwrap());
+        }
+        if o.iter().all(|&(_, h)| 2 * h as u64 > container_height as u64) {
+            tall.push(o.iter().map(|&(w, _)| w).min().unwrap());
+        }
+    }
+    one_dim_bound(&wide, container_height).max(one_dim_bound(&tall, container_width))
+}
+
+/// K1: 残りの幅がp未満かつ高さがq未満しか残らない矩形、K2: 縦横とも半分を超える矩形、
+/// K3: 縦横ともp, q以上の矩形。K3はK1のビンに入らないため、K1の数にK2∪K3の必要数を足せる
+fn l2_bound(rectangles: &[Rectangle], container_width: u32, container_height: u32, allow_rotate: bool) -> usize {
+    let (cw, ch) = (container_width as u64, container_height as u64);
+    let bin_area = cw * ch;
+    let dims: Vec<Vec<(u64, u64)>> = rectangles
+        .iter()
+        .map(|&r| orientations(r, allow_rotate).into_iter().map(|(w, h)| (w as u64, h as u64)).collect())
+        .collect();
+    // 集合が変わるのは辺の長さ+1と、ビンの長さ-辺の長さ+1の位置だけ
+    let breakpoints = |cap: u64, side: &dyn Fn(&(u64, u64)) -> u64| {
+        let mut values = vec![1];
+        for o in dims.iter().flatten() {
+            values.push(side(o) + 1);
+            values.push((cap + 1).saturating_sub(side(o)));
+        }
+        values.retain(|&v| v >= 1 && 2 * v <= cap);
+        values.sort_unstable();
+        values.dedup();
+        values
+    };
+    let ps = breakpoints(cw, &|o| o.0);
+    let qs = breakpoints(ch, &|o| o.1);
+    let mut best = 0;
+    for &p in &ps {
+        for &q in &qs {
+            let (mut k1, mut k2, mut area) = (0, 0, 0u64);
+            for (o, r) in dims.iter().zip(rectangles) {
+                if o.iter().all(|&(w, h)| w > cw - p && h > ch - q) {
+                    k1 += 1;
+                } else if o.iter().all(|&(w, h)| 2 * w > cw && 2 * h > ch) {
+                    k2 += 1;
+                    area += r.width as u64 * r.height as u64;
+                } else if o.iter().all(|&(w, h)| w >= p && h >= q) {
+                    area += r.width as u64 * r.height as u64;
+                }
+            }
+            best = best.max(k1 + k2.max(area.div_ceil(bin_area) as usize));
+        }
+    }
+    best
+}
+
+/// 必要ビン数の下界を計算する関数。どの向きでもビンに入らない矩形は無視する
+pub fn lower_bounds(rectangles: &[Rectangle], container_width: u32, container_height: u32, allow_rotate: bool) -> LowerBounds {
+    if container_width == 0 || container_height == 0 {
+        return LowerBounds { continuous: 0, l1: 0, l2: 0 };
+    }
+    let rects: Vec<Rectangle> = rectangles
+        .iter()
+        .copied()
+        .filter(|&r| orientations(r, allow_rotate).iter().any(|&(w, h)| w <= container_width && h <= container_height))
+        .collect();
+    LowerBounds {
+        continuous: continuous_lower_bound(&rects, container_width, container_height),
+        l1: l1_bound(&rects, container_width, container_height, allow_rotate),
+        l2: l2_bound(&rects, container_width, container_height, allow_rotate),
+    }
+}
@@ -0,0 +1,79 @@
+use crate::PackedRect;
+
+/// 圧縮の方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gravity {
+    /// y = 0 に向かって下へ詰める
+    Down,
+    /// x = 0 に向かって左へ詰める
+    Left,
+    /// 下と左を交互に、動かなくなるまで繰り返す
+    DownLeft,
+}
+
+/// 配置済みの矩形を指定方向へ他の矩形かビンの端に接するまで移動させる関数。
+/// 移動は直線的に行うため重なりは生じない
+pub fn compact(rects: &mut [PackedRect], gravity: Gravity) {
+    compact_with_kerf(rects, gravity, 0);
+}
+
+/// 矩形同士の間にkerf（刃の厚み）以上の隙間を残して詰める関数。
+/// ビンの端との間には隙間を残さない
+pub fn compact_with_kerf(rects: &mut [PackedRect], gravity: Gravity, kerf: u32) {
+    loop {
+        let moved = match gravity {
+            Gravity::Down => slide(rects, true, kerf),
+            Gravity::Left => slide(rects, false, kerf),
+            Gravity::DownLeft => {
+                let down = slide(rects, true, kerf);
+                let left = slide(rects, false, kerf);
+                down || left
+            }
+        };
+        if !moved {
+            break;
+        }
+    }
+}
+
+/// 1回分の移動を行い、1つでも動いたらtrueを返す
+fn slide(rects: &mut [PackedRect], down: bool, kerf: u32) -> bool {
+    // 原点に近いものから順に動かす
+    let mut order: Vec<usize> = (0..rects.len()).collect();
+    if down {
+        order.sort_by_key(|&i| (rects[i].y, rects[i].x));
+    } else {
+        order.sort_by_key(|&i| (rects[i].x, rects[i].y));
+    }
+    let mut moved = false;
+    for i in order {
+        let r = rects[i];
+        // 移動方向に並ぶ矩形のうち最も近い辺からkerf離れた位置まで移動できる。
+        // 横方向の隙間がkerf未満の矩形も移動の妨げになる
+        let limit = rects
+            .iter()
+            .enumerate()
+            .filter(|&(j, o)| j != i && o.bin_id == r.bin_id)
+            .filter_map(|(_, o)| {
+                if down {
+                    let overlaps = o.x < r.x + r.width + kerf && r.x < o.x + o.width + kerf;
+                    (overlaps && o.y + o.height <= r.y).then_some(o.y + o.height + kerf)
+                } else {
+                    let overlaps = o.y < r.y + r.height + kerf && r.y < o.y + o.height + kerf;
+                    (overlaps && o.x + o.width <= r.x).then_some(o.x + o.width + kerf)
+                }
+            })
+            .max()
+            .unwrap_or(0);
+        let current = if down { r.y } else { r.x };
+        if limit < current {
+            if down {
+                rects[i].y = limit;
+            } else {
+                rects[i].x = limit;
+            }
+            moved = true;
+        }
+    }
+    moved
+}
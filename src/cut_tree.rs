@@ -0,0 +1,202 @@
+use crate::{Rectangle, Position, GuillotineRect};
+
+/// 切断の向き
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CutOrientation {
+    /// x軸に平行な切断（位置はy座標）
+    Horizontal,
+    /// y軸に平行な切断（位置はx座標）
+    Vertical,
+}
+
+impl CutOrientation {
+    fn flipped(self) -> Self {
+        match self {
+            CutOrientation::Horizontal => CutOrientation::Vertical,
+            CutOrientation::Vertical => CutOrientation::Horizontal,
+        }
+    }
+}
+
+/// 1回の切断
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cut {
+    pub orientation: CutOrientation,
+    /// 切断線の座標。kerfがある場合は刃がこの座標から正の方向に材料を削る
+    pub position: u32,
+    /// 切断される部材
+    pub piece: GuillotineRect,
+    /// 段数（ビン全体に対する切断が1）
+    pub depth: u32,
+}
+
+/// 切断木のノード。平行な切断をまとめて1段として扱う
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CutNode {
+    pub piece: GuillotineRect,
+    /// 部材がそのまま部品になる場合、そのインデックス
+    pub item: Option<usize>,
+    /// この部材に入れる平行な切断（座標順）
+    pub cuts: Vec<Cut>,
+    /// 切断で得られる部材（座標順、端材を含む）
+    pub children: Vec<CutNode>,
+}
+
+impl CutNode {
+    /// 切断も部品もない端材か
+    pub fn is_waste(&self) -> bool {
+        self.item.is_none() && self.cuts.is_empty()
+    }
+
+    /// 行きがけ順に並べた切断の手順
+    pub fn cut_sequence(&self) -> Vec<Cut> {
+        let mut sequence = Vec::new();
+        self.collect_cuts(&mut sequence);
+        sequence
+    }
+
+    fn collect_cuts(&self, sequence: &mut Vec<Cut>) {
+        sequence.extend_from_slice(&self.cuts);
+        for child in &self.children {
+            child.collect_cuts(sequence);
+        }
+    }
+
+    /// 部品のインデックスを付け替える
+    pub(crate) fn map_items(&mut self, f: &dyn Fn(usize) -> usize) {
+        self.item = self.item.map(f);
+        for child in &mut self.children {
+            child.map_items(f);
+        }
+    }
+}
+
+/// (x, y, 幅, 高さ)
+pub(crate) type Item = (u32, u32, u32, u32);
+
+/// 配置結果からGuillotine切断の木を復元する関数。
+/// ビン全体を貫く切断だけで部品を切り出せない配置の場合はNoneを返す
+pub fn cut_tree(placements: &[(Rectangle, Position, bool)], container_width: u32, container_height: u32, kerf: u32) -> Option<CutNode> {
+    let items: Vec<Item> = placements
+        .iter()
+        .map(|&(r, p, rotated)| {
+            let (w, h) = if rotated { (r.height, r.width) } else { (r.width, r.height) };
+            (p.x, p.y, w, h)
+        })
+        .collect();
+    let sheet = GuillotineRect { position: Position { x: 0, y: 0 }, width: container_width, height: container_height };
+    let indices: Vec<usize> = (0..items.len()).collect();
+    split(&items, sheet, &indices, kerf, 1, CutOrientation::Horizontal)
+}
+
+fn split(items: &[Item], piece: GuillotineRect, indices: &[usize], kerf: u32, depth: u32, preferred: CutOrientation) -> Option<CutNode> {
+    let leaf = CutNode { piece, item: None, cuts: Vec::new(), children: Vec::new() };
+    if indices.is_empty() {
+        return Some(leaf);
+    }
+    if let [i] = *indices {
+        let (x, y, w, h) = items[i];
+        if (x, y, w, h) == (piece.position.x, piece.position.y, piece.width, piece.height) {
+            return Some(CutNode { item: Some(i), ..leaf });
+        }
+    }
+    for orientation in [preferred, preferred.flipped()] {
+        let positions = cut_positions(items, piece, indices, kerf, orientation);
+        if positions.is_empty() {
+            continue;
+        }
+        let cuts = positions
+            .iter()
+            .map(|&position| Cut { orientation, position, piece, depth })
+            .collect();
+        let mut children = Vec::new();
+        for sub in sub_pieces(piece, &positions, kerf, orientation) {
+            let inside = items_inside(items, indices, sub);
+            children.push(split(items, sub, &inside, kerf, depth + 1, orientation.flipped())?);
+        }
+        return Some(CutNode { cuts, children, ..leaf });
+    }
+    None
+}
+
+/// 部材に完全に含まれる部品
+pub(crate) fn items_inside(items: &[Item], indices: &[usize], piece: GuillotineRect) -> Vec<usize> {
+    indices
+        .iter()
+        .copied()
+        .filter(|&i| {
+            let (x, y, w, h) = items[i];
+            x >= piece.position.x && y >= piece.position.y
+                && x + w <= piece.position.x + piece.width && y + h <= piece.position.y + piece.height
+        })
+        .collect()
+}
+
+/// 部品を横切らずに入れられる平行な切断の座標
+pub(crate) fn cut_positions(items: &[Item], piece: GuillotineRect, indices: &[usize], kerf: u32, orientation: CutOrientation) -> Vec<u32> {
+    // 切断方向に直交する軸での区間
+    let span = |i: usize| {
+        let (x, y, w, h) = items[i];
+        match orientation {
+            CutOrientation::Vertical => (x, x + w),
+            CutOrientation::Horizontal => (y, y + h),
+        }
+    };
+    let (start, end) = match orientation {
+        CutOrientation::Vertical => (piece.position.x, piece.position.x + piece.width),
+        CutOrientation::Horizontal => (piece.position.y, piece.position.y + piece.height),
+    };
+    let mut candidates: Vec<u32> = Vec::new();
+    for &i in indices {
+        let (lo, hi) = span(i);
+        candidates.push(hi);
+        if let Some(c) = lo.checked_sub(kerf) {
+            candidates.push(c);
+        }
+    }
+    candidates.retain(|&c| c > start && c < end);
+    candidates.sort_unstable();
+    candidates.dedup();
+    let mut positions: Vec<u32> = Vec::new();
+    for c in candidates {
+        let crosses = indices.iter().any(|&i| {
+            let (lo, hi) = span(i);
+            lo < c + kerf && hi > c
+        });
+        if crosses || positions.last().is_some_and(|&last| c < last + kerf.max(1)) {
+            continue;
+        }
+        positions.push(c);
+    }
+    positions
+}
+
+/// 切断で得られる部材（幅が0のものは除く）
+pub(crate) fn sub_pieces(piece: GuillotineRect, positions: &[u32], kerf: u32, orientation: CutOrientation) -> Vec<GuillotineRect> {
+    let (start, end) = match orientation {
+        CutOrientation::Vertical => (piece.position.x, piece.position.x + piece.width),
+        CutOrientation::Horizontal => (piece.position.y, piece.position.y + piece.height),
+    };
+    let mut bounds = vec![start];
+    for &c in positions {
+        bounds.push(c);
+        bounds.push((c + kerf).min(end));
+    }
+    bounds.push(end);
+    bounds
+        .chunks(2)
+        .filter(|pair| pair[1] > pair[0])
+        .map(|pair| match orientation {
+            CutOrientation::Vertical => GuillotineRect {
+                position: Position { x: pair[0], y: piece.position.y },
+                width: pair[1] - pair[0],
+                height: piece.height,
+            },
+            CutOrientation::Horizontal => GuillotineRect {
+                position: Position { x: piece.position.x, y: pair[0] },
+                width: piece.width,
+                height: pair[1] - pair[0],
+            },
+        })
+        .collect()
+}
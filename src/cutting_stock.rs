@@ -0,0 +1,126 @@
+use std::cmp::Reverse;
+
+use crate::{Rectangle, Position, PackAlgorithm, Rotation};
+
+/// 同じ配置で繰り返し切り出すシートのパターン
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CuttingPattern {
+    pub placements: Vec<(Rectangle, Position, bool)>,
+    /// このパターンで切るシートの枚数
+    pub count: usize,
+}
+
+/// カッティングストックの計画
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CuttingPlan {
+    pub patterns: Vec<CuttingPattern>,
+    /// 使用するシートの合計枚数
+    pub sheets: usize,
+    /// シートに入らず残った(矩形, 枚数)
+    pub unplaced: Vec<(Rectangle, usize)>,
+}
+
+/// (繰り返し回数, 歩留まり, 配置, 1枚あたりの枚数)
+type Candidate = (usize, f64, Vec<(Rectangle, Position, bool)>, Vec<usize>);
+
+/// 必要数を満たすシートのパターンと繰り返し回数を求める関数。
+/// `tolerance` は歩留まりの許容低下幅（0.05なら5ポイント）で、
+/// その範囲内なら繰り返し回数の多いパターンを選んでパターン数を減らす
+pub fn cutting_stock(demands: &[(Rectangle, usize)], sheet_width: u32, sheet_height: u32, algorithm: PackAlgorithm, allow_rotate: bool, tolerance: f64) -> CuttingPlan {
+    cutting_stock_with_kerf(demands, sheet_width, sheet_height, algorithm, allow_rotate, tolerance, 0)
+}
+
+/// 部品同士の間にkerf（刃の厚み）分の隙間を空けるカッティングストック
+pub fn cutting_stock_with_kerf(demands: &[(Rectangle, usize)], sheet_width: u32, sheet_height: u32, algorithm: PackAlgorithm, allow_rotate: bool, tolerance: f64, kerf: u32) -> CuttingPlan {
+    // 同じ矩形の必要数はまとめる
+    let mut types: Vec<Rectangle> = Vec::new();
+    let mut remaining: Vec<usize> = Vec::new();
+    for &(rect, quantity) in demands {
+        match types.iter().position(|&t| t == rect) {
+            Some(i) => remaining[i] += quantity,
+            None => {
+                types.push(rect);
+                remaining.push(quantity);
+            }
+        }
+    }
+    let sheet_area = sheet_width as u64 * sheet_height as u64;
+    let mut patterns: Vec<CuttingPattern> = Vec::new();
+
+    while remaining.iter().any(|&d| d > 0) {
+        let mut candidates: Vec<Candidate> = Vec::new();
+        for repeat in repeat_targets(&remaining) {
+            let limits: Vec<usize> = remaining.iter().map(|&d| d / repeat).collect();
+            let placements = place_pattern(&types, &limits, sheet_width, sheet_height, algorithm, allow_rotate, kerf);
+            if placements.is_empty() {
+                continue;
+            }
+            let per_sheet: Vec<usize> = types
+                .iter()
+                .map(|&t| placements.iter().filter(|(r, _, _)| *r == t).count())
+                .collect();
+            let count = per_sheet
+                .iter()
+                .zip(&remaining)
+                .filter(|(&a, _)| a > 0)
+                .map(|(&a, &d)| d / a)
+                .min()
+                .unwrap_or(0);
+            let used: u64 = placements.iter().map(|(r, _, _)| r.width as u64 * r.height as u64).sum();
+            candidates.push((count, used as f64 / sheet_area as f64, placements, per_sheet));
+        }
+        let Some(best_utilization) = candidates.iter().map(|c| c.1).max_by(f64::total_cmp) else { break };
+        // 歩留まりが許容範囲内の候補から、繰り返し回数が最も多いものを選ぶ
+        let Some((count, _, placements, per_sheet)) = candidates
+            .into_iter()
+            .filter(|c| c.1 >= best_utilization - tolerance - 1e-9)
+            .max_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)))
+        else {
+            break;
+        };
+        for (d, a) in remaining.iter_mut().zip(&per_sheet) {
+            *d -= a * count;
+        }
+        // 同じ配置のシートは1つのパターンにまとめる
+        match patterns.iter_mut().find(|p| p.placements == placements) {
+            Some(pattern) => pattern.count += count,
+            None => patterns.push(CuttingPattern { placements, count }),
+        }
+    }
+
+    CuttingPlan {
+        sheets: patterns.iter().map(|p| p.count).sum(),
+        patterns,
+        unplaced: types.into_iter().zip(remaining).filter(|&(_, d)| d > 0).collect(),
+    }
+}
+
+/// 試す繰り返し回数: 1と、各必要数を小さい整数で割った値
+fn repeat_targets(remaining: &[usize]) -> Vec<usize> {
+    let mut targets = vec![1];
+    for &d in remaining {
+        for m in 1..=8 {
+            if d / m > 1 {
+                targets.push(d / m);
+            }
+        }
+    }
+    targets.sort_unstable();
+    targets.dedup();
+    targets
+}
+
+/// 種類ごとの上限枚数までを面積の大きい順に1枚のシートへ配置する
+fn place_pattern(types: &[Rectangle], limits: &[usize], sheet_width: u32, sheet_height: u32, algorithm: PackAlgorithm, allow_rotate: bool, kerf: u32) -> Vec<(Rectangle, Position, bool)> {
+    let mut rects: Vec<Rectangle> = types
+        .iter()
+        .zip(limits)
+        .flat_map(|(&t, &n)| {
+            // 面積的に1枚に入りきらない枚数は並べない
+            let fit = (sheet_width as u64 * sheet_height as u64) / (t.width as u64 * t.height as u64).max(1);
+            std::iter::repeat_n(t, n.min(fit as usize))
+        })
+        .collect();
+    rects.sort_by_key(|r| Reverse(r.width as u64 * r.height as u64));
+    algorithm.place_with_kerf(&rects, sheet_width, Some(sheet_height), Rotation::All(allow_rotate), kerf)
+}
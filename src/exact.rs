@@ -0,0 +1,374 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::{Rectangle, Position, lower_bounds, maxrects_placement, skyline_placement};
+
+/// 厳密解法の探索打ち切り条件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ExactLimits {
+    pub max_nodes: Option<u64>,
+    pub time_limit: Option<Duration>,
+}
+
+/// 1つのビンに全矩形が入るかの判定結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FitResult {
+    /// 入る（配置例付き）
+    Fits(Vec<(Rectangle, Position, bool)>),
+    /// 入らないことが証明された
+    DoesNotFit,
+    /// 打ち切り条件に達したため不明
+    Unknown,
+}
+
+/// 最小ビン数の探索結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinBinsResult {
+    pub bins: usize,
+    /// (ビン番号, 矩形, 位置, 回転)
+    pub placements: Vec<(usize, Rectangle, Position, bool)>,
+    /// falseの場合は打ち切り条件に達しており、これまでに見つかった最良解
+    pub optimal: bool,
+    pub lower_bound: usize,
+    /// どの向きでもビンに入らないため探索から除いた矩形。
+    /// `optimal` はこれらを除いた矩形についての最適性を表す
+    pub unplaced: Vec<Rectangle>,
+}
+
+struct Budget {
+    limits: ExactLimits,
+    start: Instant,
+    nodes: u64,
+    exhausted: bool,
+}
+
+impl Budget {
+    fn new(limits: ExactLimits) -> Self {
+        Self { limits, start: Instant::now(), nodes: 0, exhausted: false }
+    }
+
+    /// ノードを1つ消費し、打ち切るべきならtrueを返す
+    fn tick(&mut self) -> bool {
+        self.nodes += 1;
+        if self.limits.max_nodes.is_some_and(|max| self.nodes > max)
+            || (self.nodes.is_multiple_of(1024) && self.limits.time_limit.is_some_and(|limit| self.start.elapsed() >= limit))
+        {
+            self.exhausted = true;
+        }
+        self.exhausted
+    }
+}
+
+fn orientations(rect: Rectangle, allow_rotate: bool) -> Vec<(u32, u32, bool)> {
+    let mut result = vec![(rect.width, rect.height, false)];
+    if allow_rotate && rect.width != rect.height {
+        result.push((rect.height, rect.width, true));
+    }
+    result
+}
+
+fn fits_alone(rect: Rectangle, container_width: u32, container_height: u32, allow_rotate: bool) -> bool {
+    orientations(rect, allow_rotate).iter().any(|&(w, h, _)| w <= container_width && h <= container_height)
+}
+
+/// 部分和（normal pattern）: 他の矩形の辺の和で表せる座標だけを候補にすれば十分
+fn normal_patterns(rects: &[Rectangle], limit: u32, allow_rotate: bool, horizontal: bool) -> Vec<u32> {
+    let mut reachable = vec![false; limit as usize + 1];
+    reachable[0] = true;
+    for &rect in rects {
+        let sides: Vec<u32> = orientations(rect, allow_rotate)
+            .iter()
+            .map(|&(w, h, _)| if horizontal { w } else { h })
+            .collect();
+        for v in (0..=limit as usize).rev() {
+            if sides.iter().any(|&s| s as usize <= v && reachable[v - s as usize]) {
+                reachable[v] = true;
+            }
+        }
+    }
+    (0..=limit).filter(|&v| reachable[v as usize]).collect()
+}
+
+/// 全矩形が1つのビンに入るかを分枝限定法で厳密に判定する関数
+pub fn exact_fit(rectangles: &[Rectangle], container_width: u32, container_height: u32, allow_rotate: bool, limits: &ExactLimits) -> FitResult {
+    let mut budget = Budget::new(*limits);
+    fit_with_budget(rectangles, container_width, container_height, allow_rotate, &mut budget)
+}
+
+fn fit_with_budget(rectangles: &[Rectangle], container_width: u32, container_height: u32, allow_rotate: bool, budget: &mut Budget) -> FitResult {
+    let total_area: u64 = rectangles.iter().map(|r| r.width as u64 * r.height as u64).sum();
+    if total_area > container_width as u64 * container_height as u64
+        || !rectangles.iter().all(|&r| fits_alone(r, container_width, container_height, allow_rotate))
+    {
+        return FitResult::DoesNotFit;
+    }
+    // 面積の大きい順に並べ、同じ矩形は隣接させる
+    let mut order: Vec<usize> = (0..rectangles.len()).collect();
+    order.sort_by_key(|&i| {
+        let r = rectangles[i];
+        (std::cmp::Reverse(r.width as u64 * r.height as u64), std::cmp::Reverse(r.width.max(r.height)), r.width, r.height)
+    });
+    let sorted: Vec<Rectangle> = order.iter().map(|&i| rectangles[i]).collect();
+    let xs = normal_patterns(&sorted, container_width, allow_rotate, true);
+    let ys = normal_patterns(&sorted, container_height, allow_rotate, false);
+
+    let mut search = FitSearch {
+        rects: &sorted,
+        container_width,
+        container_height,
+        allow_rotate,
+        xs,
+        ys,
+        placed: Vec::new(),
+    };
+    if search.dfs(budget) {
+        let mut result = vec![(Rectangle { width: 0, height: 0 }, Position { x: 0, y: 0 }, false); rectangles.len()];
+        for (k, &(x, y, _, _, rotated)) in search.placed.iter().enumerate() {
+            result[order[k]] = (sorted[k], Position { x, y }, rotated);
+        }
+        FitResult::Fits(result)
+    } else if budget.exhausted {
+        FitResult::Unknown
+    } else {
+        FitResult::DoesNotFit
+    }
+}
+
+struct FitSearch<'a> {
+    rects: &'a [Rectangle],
+    container_width: u32,
+    container_height: u32,
+    allow_rotate: bool,
+    xs: Vec<u32>,
+    ys: Vec<u32>,
+    /// (x, y, w, h, rotated)
+    placed: Vec<(u32, u32, u32, u32, bool)>,
+}
+
+impl FitSearch<'_> {
+    fn dfs(&mut self, budget: &mut Budget) -> bool {
+        let k = self.placed.len();
+        if k == self.rects.len() {
+            return true;
+        }
+        if budget.tick() {
+            return false;
+        }
+        let rect = self.rects[k];
+        // 同一矩形の対称性除去: 直前の同一矩形より後ろの位置にしか置かない
+        let previous = (k > 0 && self.rects[k - 1] == rect).then(|| {
+            let (x, y, _, _, rotated) = self.placed[k - 1];
+            (y, x, rotated)
+        });
+        for (w, h, rotated) in orientations(rect, self.allow_rotate) {
+            if w > self.container_width || h > self.container_height {
+                continue;
+            }
+            for yi in 0..self.ys.len() {
+                let y = self.ys[yi];
+                if y + h > self.container_height {
+                    break;
+                }
+                for xi in 0..self.xs.len() {
+                    let x = self.xs[xi];
+                    if x + w > self.container_width {
+                        break;
+                    }
+                    if previous.is_some_and(|p| (y, x, rotated) <= p) {
+                        continue;
+                    }
+                    let overlap = self.placed.iter().any(|&(px, py, pw, ph, _)| {
+                        x < px + pw && px < x + w && y < py + ph && py < y + h
+                    });
+                    if overlap {
+                        continue;
+                    }
+                    self.placed.push((x, y, w, h, rotated));
+                    if self.dfs(budget) {
+                        return true;
+                    }
+                    self.placed.pop();
+                    if budget.exhausted {
+                        return false;
+                    }
+                }
+            }
+        }
+        false
+    }
+}
+
+/// ヒューリスティックで上界となる配置を作る
+fn heuristic_bins(rectangles: &[Rectangle], container_width: u32, container_height: u32, allow_rotate: bool) -> Vec<Vec<(Rectangle, Position, bool)>> {
+    let mut remaining: Vec<Rectangle> = rectangles.to_vec();
+    remaining.sort_by_key(|r| std::cmp::Reverse(r.width as u64 * r.height as u64));
+    let mut bins = Vec::new();
+    while !remaining.is_empty() {
+        let area = |p: &Vec<(Rectangle, Position, bool)>| p.iter().map(|(r, _, _)| r.width as u64 * r.height as u64).sum::<u64>();
+        let a = maxrects_placement(&remaining, container_width, container_height, allow_rotate);
+        let b = skyline_placement(&remaining, container_width, container_height, allow_rotate);
+        let placed = if area(&b) > area(&a) { b } else { a };
+        if placed.is_empty() {
+            break;
+        }
+        for (r, _, _) in &placed {
+            let i = remaining.iter().position(|x| x == r).unwrap();
+            remaining.remove(i);
+        }
+        bins.push(placed);
+    }
+    bins
+}
+
+/// 全矩形を入れるのに必要な最小ビン数を分枝限定法で求める関数。
+/// どの向きでもビンに入らない矩形は対象外とし、`unplaced` で返す
+pub fn exact_min_bins(rectangles: &[Rectangle], container_width: u32, container_height: u32, allow_rotate: bool, limits: &ExactLimits) -> MinBinsResult {
+    let (rects, unplaced): (Vec<Rectangle>, Vec<Rectangle>) = rectangles
+        .iter()
+        .partition(|&&r| fits_alone(r, container_width, container_height, allow_rotate));
+    let lower_bound = lower_bounds(&rects, container_width, container_height, allow_rotate).best();
+
+    let heuristic = heuristic_bins(&rects, container_width, container_height, allow_rotate);
+    let mut best = MinBinsResult {
+        bins: heuristic.len(),
+        placements: heuristic
+            .iter()
+            .enumerate()
+            .flat_map(|(bin, placed)| placed.iter().map(move |&(r, p, rot)| (bin, r, p, rot)))
+            .collect(),
+        optimal: heuristic.len() <= lower_bound,
+        lower_bound,
+        unplaced,
+    };
+    if best.optimal {
+        return best;
+    }
+
+    let mut budget = Budget::new(*limits);
+    let mut order: Vec<Rectangle> = rects.clone();
+    order.sort_by_key(|r| (std::cmp::Reverse(r.width as u64 * r.height as u64), r.width, r.height));
+    // 小さいビン数から順に、その数で入るかを調べる
+    for k in lower_bound..best.bins {
+        let mut search = BinSearch {
+            rects: &order,
+            container_width,
+            container_height,
+            allow_rotate,
+            bins: vec![Vec::new(); k],
+            assigned: Vec::new(),
+            memo: HashMap::new(),
+        };
+        match search.assign(0, &mut budget) {
+            Some(true) => {
+                best.bins = k;
+                best.placements = search.layout(&mut budget);
+                best.optimal = true;
+                return best;
+            }
+            Some(false) => {}
+            None => return best,
+        }
+    }
+    best.optimal = true;
+    best
+}
+
+struct BinSearch<'a> {
+    rects: &'a [Rectangle],
+    container_width: u32,
+    container_height: u32,
+    allow_rotate: bool,
+    bins: Vec<Vec<Rectangle>>,
+    /// 割り当て済みの各矩形のビン番号
+    assigned: Vec<usize>,
+    memo: HashMap<Vec<(u32, u32)>, FitResult>,
+}
+
+impl BinSearch<'_> {
+    fn key(contents: &[Rectangle]) -> Vec<(u32, u32)> {
+        let mut key: Vec<(u32, u32)> = contents.iter().map(|r| (r.width, r.height)).collect();
+        key.sort_unstable();
+        key
+    }
+
+    fn check(&mut self, contents: &[Rectangle], budget: &mut Budget) -> FitResult {
+        let key = Self::key(contents);
+        if let Some(result) = self.memo.get(&key) {
+            return result.clone();
+        }
+        let result = fit_with_budget(contents, self.container_width, self.container_height, self.allow_rotate, budget);
+        if result != FitResult::Unknown {
+            self.memo.insert(key, result.clone());
+        }
+        result
+    }
+
+    /// 矩形kから先をビンに割り当てる。Noneは打ち切り
+    fn assign(&mut self, k: usize, budget: &mut Budget) -> Option<bool> {
+        if k == self.rects.len() {
+            return Some(true);
+        }
+        if budget.tick() {
+            return None;
+        }
+        let rect = self.rects[k];
+        let bin_area = self.container_width as u64 * self.container_height as u64;
+        let remaining_area: u64 = self.rects[k..].iter().map(|r| r.width as u64 * r.height as u64).sum();
+        let free_area: u64 = self
+            .bins
+            .iter()
+            .map(|b| bin_area - b.iter().map(|r| r.width as u64 * r.height as u64).sum::<u64>())
+            .sum();
+        if remaining_area > free_area {
+            return Some(false);
+        }
+        // 同一矩形は直前のコピーと同じかそれ以降のビンにのみ入れる
+        let first_bin = if k > 0 && self.rects[k - 1] == rect { self.assigned[k - 1] } else { 0 };
+        let mut tried_empty = false;
+        for bin in first_bin..self.bins.len() {
+            // 空のビンは区別できないので1つだけ試す
+            if self.bins[bin].is_empty() {
+                if tried_empty {
+                    continue;
+                }
+                tried_empty = true;
+            }
+            self.bins[bin].push(rect);
+            let contents = self.bins[bin].clone();
+            let feasible = match self.check(&contents, budget) {
+                FitResult::Fits(_) => true,
+                FitResult::DoesNotFit => false,
+                FitResult::Unknown => {
+                    self.bins[bin].pop();
+                    return None;
+                }
+            };
+            if feasible {
+                self.assigned.push(bin);
+                let result = self.assign(k + 1, budget);
+                self.assigned.pop();
+                match result {
+                    Some(true) => return Some(true),
+                    Some(false) => {}
+                    None => {
+                        self.bins[bin].pop();
+                        return None;
+                    }
+                }
+            }
+            self.bins[bin].pop();
+        }
+        Some(false)
+    }
+
+    fn layout(&mut self, budget: &mut Budget) -> Vec<(usize, Rectangle, Position, bool)> {
+        let bins = self.bins.clone();
+        let mut placements = Vec::new();
+        for (bin, contents) in bins.iter().enumerate() {
+            if let FitResult::Fits(placed) = self.check(contents, budget) {
+                placements.extend(placed.into_iter().map(|(r, p, rot)| (bin, r, p, rot)));
+            }
+        }
+        placements
+    }
+}
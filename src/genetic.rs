@@ -0,0 +1,173 @@
+use std::time::{Duration, Instant};
+
+use crate::{Rectangle, Position};
+use crate::annealing::{Fitness, PlacementFn, Sequence};
+use crate::rng::Rng;
+
+/// 遺伝的アルゴリズムの設定
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeneticConfig {
+    pub seed: u64,
+    /// 島ごとの個体数
+    pub population_size: usize,
+    pub generations: usize,
+    pub crossover_rate: f64,
+    pub mutation_rate: f64,
+    /// 次世代にそのまま残す上位個体の数
+    pub elitism: usize,
+    /// 島の数（1なら島モデルを使わない）
+    pub islands: usize,
+    /// 何世代ごとに島の間で最良個体を移住させるか
+    pub migration_interval: usize,
+    pub time_limit: Option<Duration>,
+    pub allow_rotate: bool,
+}
+
+impl Default for GeneticConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            population_size: 30,
+            generations: 100,
+            crossover_rate: 0.9,
+            mutation_rate: 0.2,
+            elitism: 2,
+            islands: 1,
+            migration_interval: 10,
+            time_limit: None,
+            allow_rotate: true,
+        }
+    }
+}
+
+struct Individual {
+    genome: Sequence,
+    fitness: Fitness,
+    placed: Vec<(Rectangle, Position, bool)>,
+}
+
+/// 順序交叉（OX）と向きの一様交叉
+fn crossover(a: &Sequence, b: &Sequence, rng: &mut Rng) -> Sequence {
+    let n = a.order.len();
+    let (mut lo, mut hi) = (rng.below(n), rng.below(n));
+    if lo > hi {
+        std::mem::swap(&mut lo, &mut hi);
+    }
+    let mut taken = vec![false; n];
+    let mut order = vec![usize::MAX; n];
+    for k in lo..=hi {
+        order[k] = a.order[k];
+        taken[a.order[k]] = true;
+    }
+    let mut fill = b.order.iter().filter(|&&i| !taken[i]);
+    for slot in order.iter_mut().filter(|slot| **slot == usize::MAX) {
+        *slot = *fill.next().unwrap();
+    }
+    let rotated = (0..n)
+        .map(|i| if rng.below(2) == 0 { a.rotated[i] } else { b.rotated[i] })
+        .collect();
+    Sequence { order, rotated }
+}
+
+fn mutate(genome: &mut Sequence, rectangles: &[Rectangle], allow_rotate: bool, rng: &mut Rng) {
+    let n = genome.order.len();
+    let i = rng.below(n);
+    let idx = genome.order[i];
+    if allow_rotate && rectangles[idx].width != rectangles[idx].height && rng.below(2) == 0 {
+        genome.rotated[idx] = !genome.rotated[idx];
+    } else {
+        let j = rng.below(n);
+        genome.order.swap(i, j);
+    }
+}
+
+/// 配置順と向きを遺伝的アルゴリズムで探索する関数。
+/// 与えられた順序での貪欲配置より悪い結果は返さない
+pub fn genetic_placement(rectangles: &[Rectangle], container_width: u32, container_height: u32, decoder: PlacementFn, config: &GeneticConfig) -> Vec<(Rectangle, Position, bool)> {
+    let greedy = decoder(rectangles, container_width, container_height, config.allow_rotate);
+    let n = rectangles.len();
+    if n == 0 {
+        return greedy;
+    }
+    let mut rng = Rng::new(config.seed);
+    let population_size = config.population_size.max(2);
+    let evaluate = |genome: Sequence| {
+        let placed = genome.decode(rectangles, container_width, container_height, decoder);
+        Individual { fitness: Fitness::of(&placed), genome, placed }
+    };
+
+    // 初期集団: 入力順、面積の降順、残りはランダムな順列
+    let mut by_area = Sequence::identity(n);
+    by_area.order.sort_by_key(|&i| std::cmp::Reverse(rectangles[i].width as u64 * rectangles[i].height as u64));
+    let mut islands: Vec<Vec<Individual>> = (0..config.islands.max(1))
+        .map(|_| {
+            let mut population = vec![evaluate(Sequence::identity(n)), evaluate(by_area.clone())];
+            while population.len() < population_size {
+                let mut genome = Sequence::identity(n);
+                for k in (1..n).rev() {
+                    genome.order.swap(k, rng.below(k + 1));
+                }
+                if config.allow_rotate {
+                    for flag in genome.rotated.iter_mut() {
+                        *flag = rng.below(2) == 0;
+                    }
+                }
+                population.push(evaluate(genome));
+            }
+            population
+        })
+        .collect();
+
+    let start = Instant::now();
+    for generation in 1..=config.generations {
+        if config.time_limit.is_some_and(|limit| start.elapsed() >= limit) {
+            break;
+        }
+        for population in islands.iter_mut() {
+            population.sort_by_key(|ind| std::cmp::Reverse(ind.fitness));
+            let mut next: Vec<Individual> = Vec::with_capacity(population_size);
+            for elite in population.iter().take(config.elitism.min(population_size)) {
+                next.push(Individual { genome: elite.genome.clone(), fitness: elite.fitness, placed: elite.placed.clone() });
+            }
+            while next.len() < population_size {
+                // 2個体のトーナメント選択
+                let select = |rng: &mut Rng| {
+                    let (a, b) = (rng.below(population.len()), rng.below(population.len()));
+                    if population[a].fitness >= population[b].fitness { &population[a] } else { &population[b] }
+                };
+                let p1 = select(&mut rng);
+                let p2 = select(&mut rng);
+                let mut child = if rng.next_f64() < config.crossover_rate {
+                    crossover(&p1.genome, &p2.genome, &mut rng)
+                } else {
+                    p1.genome.clone()
+                };
+                if rng.next_f64() < config.mutation_rate {
+                    mutate(&mut child, rectangles, config.allow_rotate, &mut rng);
+                }
+                next.push(evaluate(child));
+            }
+            *population = next;
+        }
+        // 島モデル: 各島の最良個体を隣の島の最悪個体と入れ替える
+        if islands.len() > 1 && config.migration_interval > 0 && generation % config.migration_interval == 0 {
+            let migrants: Vec<Sequence> = islands
+                .iter()
+                .map(|p| p.iter().max_by_key(|ind| ind.fitness).unwrap().genome.clone())
+                .collect();
+            let count = islands.len();
+            for (i, genome) in migrants.into_iter().enumerate() {
+                let target = &mut islands[(i + 1) % count];
+                let worst = (0..target.len()).min_by_key(|&k| target[k].fitness).unwrap();
+                target[worst] = evaluate(genome);
+            }
+        }
+    }
+
+    let best = islands
+        .into_iter()
+        .flatten()
+        .max_by_key(|ind| ind.fitness)
+        .unwrap();
+    if best.fitness > Fitness::of(&greedy) { best.placed } else { greedy }
+}
@@ -1,4 +1,7 @@
-use crate::{Rectangle, Position};
+use std::collections::HashSet;
+
+use crate::maxrects::FreeSpace;
+use crate::{Rectangle, Position, Rotation};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct GuillotineRect {
@@ -9,27 +12,40 @@ pub struct GuillotineRect {
 
 /// Guillotine法によるアイテム配置を行う関数
 pub fn guillotine_placement(rectangles: &[Rectangle], container_width: u32, container_height: u32, allow_rotate: bool) -> Vec<(Rectangle, Position, bool)> {
+    guillotine_placement_in(rectangles, container_width, Some(container_height), Rotation::All(allow_rotate), 0)
+}
+
+/// 切断ごとにkerf（刃の厚み）分の材料を失うGuillotine法。
+/// 矩形とビンの端が一致する場合は切断しないのでkerfは引かない
+pub fn guillotine_placement_with_kerf(rectangles: &[Rectangle], container_width: u32, container_height: u32, allow_rotate: bool, kerf: u32) -> Vec<(Rectangle, Position, bool)> {
+    guillotine_placement_in(rectangles, container_width, Some(container_height), Rotation::All(allow_rotate), kerf)
+}
+
+/// 高さを省略した場合は上方向に制限のないストリップとして配置する
+pub(crate) fn guillotine_placement_in(rectangles: &[Rectangle], container_width: u32, container_height: Option<u32>, rotation: Rotation, kerf: u32) -> Vec<(Rectangle, Position, bool)> {
     let mut placed_rectangles: Vec<(Rectangle, Position, bool)> = Vec::new();
-    let mut free_rects: Vec<GuillotineRect> = vec![GuillotineRect {
-        position: Position { x: 0, y: 0 },
-        width: container_width,
-        height: container_height,
-    }];
+    let mut free_rects: Vec<FreeSpace> = vec![FreeSpace { position: Position { x: 0, y: 0 }, width: container_width, height: container_height }];
 
-    for &rect in rectangles {
+    // 空き領域は分割されるだけなので、一度入らなかったサイズは以降も入らない
+    let mut failed: HashSet<(u32, u32, bool)> = HashSet::new();
+    for (index, &rect) in rectangles.iter().enumerate() {
+        let key = (rect.width, rect.height, rotation.allows(index));
+        if failed.contains(&key) {
+            continue;
+        }
         let mut candidates = vec![(rect.width, rect.height, false)];
-        if allow_rotate && rect.width != rect.height {
+        if rotation.allows(index) && rect.width != rect.height {
             candidates.push((rect.height, rect.width, true));
         }
         let mut best_idx = None;
-        let mut best_area = u32::MAX;
+        let mut best_area = u64::MAX;
         let mut best_pos = Position { x: 0, y: 0 };
         let mut best_rotated = false;
         for &(w, h, rotated) in &candidates {
             for (i, free) in free_rects.iter().enumerate() {
-                if w <= free.width && h <= free.height {
-                    let area = free.width * free.height;
-                    if area < best_area {
+                if free.fits(w, h) {
+                    let area = free.area();
+                    if best_idx.is_none() || area < best_area {
                         best_area = area;
                         best_idx = Some(i);
                         best_pos = free.position;
@@ -38,26 +54,30 @@ pub fn guillotine_placement(rectangles: &[Rectangle], container_width: u32, cont
                 }
             }
         }
+        if best_idx.is_none() {
+            failed.insert(key);
+        }
         if let Some(idx) = best_idx {
             placed_rectangles.push((rect, best_pos, best_rotated));
             let (w, h) = if best_rotated { (rect.height, rect.width) } else { (rect.width, rect.height) };
             let used = free_rects[idx];
             free_rects.remove(idx);
-            // Guillotine分割（右＋下）
-            let right = GuillotineRect {
-                position: Position { x: used.position.x + w, y: used.position.y },
-                width: used.width - w,
-                height: h,
+            // Guillotine分割（右＋下）。余りがある場合だけ切断し、kerf分を取り除く
+            let right = FreeSpace {
+                position: Position { x: used.position.x + w + kerf, y: used.position.y },
+                width: (used.width - w).saturating_sub(kerf),
+                height: Some(h),
             };
-            let below = GuillotineRect {
-                position: Position { x: used.position.x, y: used.position.y + h },
+            // 上方向に制限のない領域は切断後も制限のないまま残る
+            let below = FreeSpace {
+                position: Position { x: used.position.x, y: used.position.y + h + kerf },
                 width: used.width,
-                height: used.height - h,
+                height: used.height.map(|uh| (uh - h).saturating_sub(kerf)),
             };
-            if right.width > 0 && right.height > 0 {
+            if right.width > 0 && h > 0 {
                 free_rects.push(right);
             }
-            if below.width > 0 && below.height > 0 {
+            if below.width > 0 && below.height != Some(0) {
                 free_rects.push(below);
             }
         }
@@ -15,12 +15,47 @@ mod maxrects;
 mod skyline;
 mod guillotine;
 mod packer;
+mod polygon;
+mod raster;
+mod nfp;
+mod bin_search;
+mod strip;
+mod compaction;
+mod portfolio;
+mod rng;
+mod annealing;
+mod genetic;
+mod beam;
+mod exact;
+mod bounds;
+mod staged;
+mod cut_tree;
+mod remnant;
+mod cutting_stock;
+mod remnant_store;
 
 pub use bottom_left::bottom_left_placement;
 pub use maxrects::{maxrects_placement, FreeRect};
 pub use skyline::{skyline_placement, SkylineNode};
-pub use guillotine::{guillotine_placement, GuillotineRect};
-pub use packer::{Packer, PackAlgorithm, PackedRect};
+pub use guillotine::{guillotine_placement, guillotine_placement_with_kerf, GuillotineRect};
+pub use packer::{Packer, PackAlgorithm, PackedRect, PackedBin, GroupMode, BinFilter, Bin, SizeRounding, SortOrder, PackSummary, Grain, Stock, DemandReport, MaterialPool};
+pub use portfolio::{Objective, PackConfig};
+pub use annealing::{anneal_placement, AnnealingConfig, PlacementFn};
+pub use genetic::{genetic_placement, GeneticConfig};
+pub use beam::beam_search_placement;
+pub use cutting_stock::{cutting_stock, cutting_stock_with_kerf, CuttingPattern, CuttingPlan};
+pub use remnant::{find_remnants, Remnant};
+pub use remnant_store::{RemnantStore, StoredRemnant};
+pub use cut_tree::{cut_tree, Cut, CutNode, CutOrientation};
+pub use staged::{staged_guillotine_placement, StageLimit};
+pub use bounds::{lower_bounds, continuous_lower_bound, LowerBounds};
+pub use exact::{exact_fit, exact_min_bins, ExactLimits, FitResult, MinBinsResult};
+pub use bin_search::{search_bin_size, BinSizeConstraints, BinSizeResult};
+pub use strip::{strip_pack, StripLayout};
+pub use compaction::{compact, compact_with_kerf, Gravity};
+pub use polygon::{polygon_bottom_left_placement, PolygonBin};
+pub use raster::{mask_placement, OccupancyMask};
+pub use nfp::{nfp_placement, nest_parts, NestPlacement, PolygonPart};
 
 
 /// BL安定点が真にBL点かどうかを確認する関数
@@ -53,3 +88,21 @@ fn is_true_bl_point(pos: Position, placed_rectangles: &[(Rectangle, Position)])
     
     has_rect_left && has_rect_below
 }
+
+/// 矩形ごとの回転可否
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Rotation<'a> {
+    /// 全矩形に同じ設定を使う
+    All(bool),
+    /// 矩形ごとに指定する
+    PerItem(&'a [bool]),
+}
+
+impl Rotation<'_> {
+    pub(crate) fn allows(&self, index: usize) -> bool {
+        match *self {
+            Rotation::All(allow) => allow,
+            Rotation::PerItem(flags) => flags[index],
+        }
+    }
+}
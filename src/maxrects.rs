@@ -1,4 +1,6 @@
-use crate::{Rectangle, Position};
+use std::collections::HashSet;
+
+use crate::{Rectangle, Position, Rotation};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct FreeRect {
@@ -7,29 +9,55 @@ pub struct FreeRect {
     pub height: u32,
 }
 
+/// 配置中の空き領域。高さがNoneの領域は上方向に制限がない（ストリップの最上部）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FreeSpace {
+    pub position: Position,
+    pub width: u32,
+    pub height: Option<u32>,
+}
+
+impl FreeSpace {
+    pub fn fits(&self, w: u32, h: u32) -> bool {
+        w <= self.width && self.height.is_none_or(|fh| h <= fh)
+    }
+
+    /// 面積の小さい順に選ぶための値。上方向に制限のない領域は最も大きい扱い
+    pub fn area(&self) -> u64 {
+        self.height.map_or(u64::MAX, |fh| self.width as u64 * fh as u64)
+    }
+}
+
 /// MaxRects法によるアイテム配置を行う関数
 pub fn maxrects_placement(rectangles: &[Rectangle], container_width: u32, container_height: u32, allow_rotate: bool) -> Vec<(Rectangle, Position, bool)> {
+    maxrects_placement_in(rectangles, container_width, Some(container_height), Rotation::All(allow_rotate))
+}
+
+/// 高さを省略した場合は上方向に制限のないストリップとして配置する
+pub(crate) fn maxrects_placement_in(rectangles: &[Rectangle], container_width: u32, container_height: Option<u32>, rotation: Rotation) -> Vec<(Rectangle, Position, bool)> {
     let mut placed_rectangles: Vec<(Rectangle, Position, bool)> = Vec::new();
-    let mut free_rects: Vec<FreeRect> = vec![FreeRect {
-        position: Position { x: 0, y: 0 },
-        width: container_width,
-        height: container_height,
-    }];
+    let mut free_rects: Vec<FreeSpace> = vec![FreeSpace { position: Position { x: 0, y: 0 }, width: container_width, height: container_height }];
 
-    for &rect in rectangles {
+    // 空き領域は分割されるだけなので、一度入らなかったサイズは以降も入らない
+    let mut failed: HashSet<(u32, u32, bool)> = HashSet::new();
+    for (index, &rect) in rectangles.iter().enumerate() {
+        let key = (rect.width, rect.height, rotation.allows(index));
+        if failed.contains(&key) {
+            continue;
+        }
         let mut candidates = vec![(rect.width, rect.height, false)];
-        if allow_rotate && rect.width != rect.height {
+        if rotation.allows(index) && rect.width != rect.height {
             candidates.push((rect.height, rect.width, true));
         }
         let mut best_index = None;
-        let mut best_area = u32::MAX;
+        let mut best_area = u64::MAX;
         let mut best_pos = Position { x: 0, y: 0 };
         let mut best_rotated = false;
         for &(w, h, rotated) in &candidates {
             for (i, free) in free_rects.iter().enumerate() {
-                if w <= free.width && h <= free.height {
-                    let area = free.width * free.height;
-                    if area < best_area {
+                if free.fits(w, h) {
+                    let area = free.area();
+                    if best_index.is_none() || area < best_area {
                         best_area = area;
                         best_index = Some(i);
                         best_pos = free.position;
@@ -38,6 +66,9 @@ pub fn maxrects_placement(rectangles: &[Rectangle], container_width: u32, contai
                 }
             }
         }
+        if best_index.is_none() {
+            failed.insert(key);
+        }
         if let Some(idx) = best_index {
             placed_rectangles.push((rect, best_pos, best_rotated));
             let (w, h) = if best_rotated { (rect.height, rect.width) } else { (rect.width, rect.height) };
@@ -46,18 +77,18 @@ pub fn maxrects_placement(rectangles: &[Rectangle], container_width: u32, contai
             free_rects.remove(idx);
             // 右側
             if used.width > w {
-                free_rects.push(FreeRect {
+                free_rects.push(FreeSpace {
                     position: Position { x: used.position.x + w, y: used.position.y },
                     width: used.width - w,
-                    height: h,
+                    height: Some(h),
                 });
             }
-            // 下側
-            if used.height > h {
-                free_rects.push(FreeRect {
+            // 下側（上方向に制限のない領域は制限のないまま残る）
+            if used.height.is_none_or(|uh| uh > h) {
+                free_rects.push(FreeSpace {
                     position: Position { x: used.position.x, y: used.position.y + h },
                     width: used.width,
-                    height: used.height - h,
+                    height: used.height.map(|uh| uh - h),
                 });
             }
         }
@@ -0,0 +1,322 @@
+use crate::Position;
+
+type Point = (f64, f64);
+
+const EPS: f64 = 1e-6;
+
+/// 多角形部品（単純多角形）と許可する回転の分割数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolygonPart {
+    pub vertices: Vec<Position>,
+    /// 360度を何分割した回転を試すか（1なら回転なし、4なら90度刻み）
+    pub rotation_steps: u32,
+}
+
+/// NFP法による配置結果。外接矩形の左下を (x, y) に合わせる
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NestPlacement {
+    pub part_index: usize,
+    pub bin_id: usize,
+    pub x: f64,
+    pub y: f64,
+    /// 反時計回りの回転角（度）
+    pub rotation: f64,
+}
+
+impl PolygonPart {
+    pub fn new(vertices: Vec<Position>, rotation_steps: u32) -> Self {
+        Self { vertices, rotation_steps }
+    }
+
+    /// 配置結果に従って移動・回転した頂点座標を返す
+    pub fn placed_vertices(&self, placement: &NestPlacement) -> Vec<(f64, f64)> {
+        normalized(&rotate(&self.vertices, placement.rotation))
+            .into_iter()
+            .map(|(x, y)| (x + placement.x, y + placement.y))
+            .collect()
+    }
+
+    fn orientations(&self) -> Vec<OrientedPart> {
+        let steps = self.rotation_steps.max(1);
+        (0..steps)
+            .map(|i| {
+                let rotation = 360.0 * i as f64 / steps as f64;
+                let mut vertices = normalized(&rotate(&self.vertices, rotation));
+                if signed_area(&vertices) < 0.0 {
+                    vertices.reverse();
+                }
+                let vertices = remove_collinear(&vertices);
+                let width = vertices.iter().map(|p| p.0).fold(0.0, f64::max);
+                let height = vertices.iter().map(|p| p.1).fold(0.0, f64::max);
+                let pieces = convex_pieces(&vertices);
+                OrientedPart { rotation, width, height, pieces }
+            })
+            .collect()
+    }
+}
+
+struct OrientedPart {
+    rotation: f64,
+    width: f64,
+    height: f64,
+    /// 凸多角形（反時計回り）への分割
+    pieces: Vec<Vec<Point>>,
+}
+
+fn rotate(vertices: &[Position], degrees: f64) -> Vec<Point> {
+    let rad = degrees.to_radians();
+    // 90度刻みの回転で誤差が出ないよう丸める
+    let snap = |v: f64| if (v - v.round()).abs() < 1e-12 { v.round() } else { v };
+    let (sin, cos) = (snap(rad.sin()), snap(rad.cos()));
+    vertices
+        .iter()
+        .map(|v| {
+            let (x, y) = (v.x as f64, v.y as f64);
+            (x * cos - y * sin, x * sin + y * cos)
+        })
+        .collect()
+}
+
+fn normalized(points: &[Point]) -> Vec<Point> {
+    let min_x = points.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+    let min_y = points.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+    points.iter().map(|&(x, y)| (x - min_x, y - min_y)).collect()
+}
+
+fn signed_area(points: &[Point]) -> f64 {
+    let n = points.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            a.0 * b.1 - b.0 * a.1
+        })
+        .sum::<f64>()
+        / 2.0
+}
+
+fn cross(o: Point, a: Point, b: Point) -> f64 {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+fn remove_collinear(points: &[Point]) -> Vec<Point> {
+    let mut result: Vec<Point> = points.to_vec();
+    let mut i = 0;
+    while result.len() > 3 && i < result.len() {
+        let n = result.len();
+        let (prev, cur, next) = (result[(i + n - 1) % n], result[i], result[(i + 1) % n]);
+        if cross(prev, cur, next).abs() < 1e-12 {
+            result.remove(i);
+            i = i.saturating_sub(1);
+        } else {
+            i += 1;
+        }
+    }
+    result
+}
+
+fn is_convex(points: &[Point]) -> bool {
+    let n = points.len();
+    (0..n).all(|i| cross(points[i], points[(i + 1) % n], points[(i + 2) % n]) >= -1e-12)
+}
+
+/// 耳刈り取り法で反時計回りの多角形を凸な三角形に分割する
+fn convex_pieces(points: &[Point]) -> Vec<Vec<Point>> {
+    if is_convex(points) {
+        return vec![points.to_vec()];
+    }
+    let mut remaining: Vec<Point> = points.to_vec();
+    let mut pieces = Vec::new();
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let ear = (0..n).find(|&i| {
+            let (a, b, c) = (remaining[(i + n - 1) % n], remaining[i], remaining[(i + 1) % n]);
+            if cross(a, b, c) <= 1e-12 {
+                return false;
+            }
+            remaining.iter().all(|&p| {
+                p == a || p == b || p == c
+                    || !(cross(a, b, p) >= 0.0 && cross(b, c, p) >= 0.0 && cross(c, a, p) >= 0.0)
+            })
+        });
+        let Some(i) = ear else { break };
+        pieces.push(vec![remaining[(i + n - 1) % n], remaining[i], remaining[(i + 1) % n]]);
+        remaining.remove(i);
+    }
+    pieces.push(remaining);
+    pieces
+}
+
+/// 凸包（反時計回り）
+fn convex_hull(mut points: Vec<Point>) -> Vec<Point> {
+    points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+    let mut lower: Vec<Point> = Vec::new();
+    for &p in &points {
+        while lower.len() >= 2 && cross(lower[lower.len() - 2], lower[lower.len() - 1], p) <= 0.0 {
+            lower.pop();
+        }
+        lower.push(p);
+    }
+    let mut upper: Vec<Point> = Vec::new();
+    for &p in points.iter().rev() {
+        while upper.len() >= 2 && cross(upper[upper.len() - 2], upper[upper.len() - 1], p) <= 0.0 {
+            upper.pop();
+        }
+        upper.push(p);
+    }
+    lower.pop();
+    upper.pop();
+    lower.extend(upper);
+    lower
+}
+
+/// 凸多角形同士のNFP（A ⊕ -B）
+fn convex_nfp(a: &[Point], a_offset: Point, b: &[Point]) -> Vec<Point> {
+    let mut sums = Vec::with_capacity(a.len() * b.len());
+    for &pa in a {
+        for &pb in b {
+            sums.push((pa.0 + a_offset.0 - pb.0, pa.1 + a_offset.1 - pb.1));
+        }
+    }
+    convex_hull(sums)
+}
+
+/// 点が凸多角形の内部（境界を含まない）にあるか
+fn strictly_inside(poly: &[Point], p: Point) -> bool {
+    if poly.len() < 3 {
+        return false;
+    }
+    let n = poly.len();
+    (0..n).all(|i| {
+        let (a, b) = (poly[i], poly[(i + 1) % n]);
+        let len = ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt();
+        cross(a, b, p) / len > EPS
+    })
+}
+
+fn segment_intersection(a: Point, b: Point, c: Point, d: Point) -> Option<Point> {
+    let r = (b.0 - a.0, b.1 - a.1);
+    let s = (d.0 - c.0, d.1 - c.1);
+    let denom = r.0 * s.1 - r.1 * s.0;
+    if denom.abs() < 1e-12 {
+        return None;
+    }
+    let t = ((c.0 - a.0) * s.1 - (c.1 - a.1) * s.0) / denom;
+    let u = ((c.0 - a.0) * r.1 - (c.1 - a.1) * r.0) / denom;
+    if (-1e-9..=1.0 + 1e-9).contains(&t) && (-1e-9..=1.0 + 1e-9).contains(&u) {
+        Some((a.0 + t * r.0, a.1 + t * r.1))
+    } else {
+        None
+    }
+}
+
+/// 1つの向きの部品について、BL順で最も左下の配置可能な参照点を求める
+fn bottom_left_point(placed: &[(&OrientedPart, Point)], part: &OrientedPart, container_width: f64, container_height: f64) -> Option<Point> {
+    // 内接可能領域（IFP）は矩形
+    let max_x = container_width - part.width;
+    let max_y = container_height - part.height;
+    if max_x < -EPS || max_y < -EPS {
+        return None;
+    }
+    let (max_x, max_y) = (max_x.max(0.0), max_y.max(0.0));
+    let ifp = [(0.0, 0.0), (max_x, 0.0), (max_x, max_y), (0.0, max_y)];
+
+    let mut nfps: Vec<Vec<Point>> = Vec::new();
+    for &(other, offset) in placed {
+        for a in &other.pieces {
+            for b in &part.pieces {
+                let nfp = convex_nfp(a, offset, b);
+                let outside = nfp.iter().all(|p| p.0 <= EPS) || nfp.iter().all(|p| p.0 >= max_x - EPS)
+                    || nfp.iter().all(|p| p.1 <= EPS) || nfp.iter().all(|p| p.1 >= max_y - EPS);
+                if nfp.len() >= 3 && !outside {
+                    nfps.push(nfp);
+                }
+            }
+        }
+    }
+
+    // 候補点: IFPの頂点、NFPの頂点、辺同士の交点
+    let mut edges: Vec<(Point, Point)> = (0..4).map(|i| (ifp[i], ifp[(i + 1) % 4])).collect();
+    for nfp in &nfps {
+        let n = nfp.len();
+        edges.extend((0..n).map(|i| (nfp[i], nfp[(i + 1) % n])));
+    }
+    let mut candidates: Vec<Point> = ifp.to_vec();
+    for nfp in &nfps {
+        candidates.extend(nfp.iter().copied());
+    }
+    for i in 0..edges.len() {
+        for j in (i + 1)..edges.len() {
+            let (a, b) = edges[i];
+            let (c, d) = edges[j];
+            if a.0.max(b.0) < c.0.min(d.0) - EPS || c.0.max(d.0) < a.0.min(b.0) - EPS
+                || a.1.max(b.1) < c.1.min(d.1) - EPS || c.1.max(d.1) < a.1.min(b.1) - EPS {
+                continue;
+            }
+            candidates.extend(segment_intersection(a, b, c, d));
+        }
+    }
+    candidates.retain(|p| p.0 >= -EPS && p.0 <= max_x + EPS && p.1 >= -EPS && p.1 <= max_y + EPS);
+    candidates.sort_by(|a, b| (a.1, a.0).partial_cmp(&(b.1, b.0)).unwrap());
+    candidates
+        .into_iter()
+        .find(|&p| !nfps.iter().any(|nfp| strictly_inside(nfp, p)))
+        .map(|(x, y)| (x.clamp(0.0, max_x), y.clamp(0.0, max_y)))
+}
+
+/// NFP法により多角形部品を矩形ビンへBL配置する関数
+pub fn nfp_placement(parts: &[PolygonPart], container_width: u32, container_height: u32) -> Vec<NestPlacement> {
+    let oriented: Vec<Vec<OrientedPart>> = parts.iter().map(|p| p.orientations()).collect();
+    let indices: Vec<usize> = (0..parts.len()).collect();
+    place_in_bin(&oriented, &indices, 0, container_width, container_height)
+}
+
+/// 複数の矩形ビンに順番に部品を配置する関数
+pub fn nest_parts(parts: &[PolygonPart], bins: &[(u32, u32)]) -> Vec<NestPlacement> {
+    let oriented: Vec<Vec<OrientedPart>> = parts.iter().map(|p| p.orientations()).collect();
+    let mut remaining: Vec<usize> = (0..parts.len()).collect();
+    let mut placements = Vec::new();
+    for (bin_id, &(width, height)) in bins.iter().enumerate() {
+        if remaining.is_empty() {
+            break;
+        }
+        let placed = place_in_bin(&oriented, &remaining, bin_id, width, height);
+        remaining.retain(|i| !placed.iter().any(|p| p.part_index == *i));
+        placements.extend(placed);
+    }
+    placements
+}
+
+fn place_in_bin(oriented: &[Vec<OrientedPart>], indices: &[usize], bin_id: usize, container_width: u32, container_height: u32) -> Vec<NestPlacement> {
+    let (width, height) = (container_width as f64, container_height as f64);
+    let mut placed: Vec<(&OrientedPart, Point)> = Vec::new();
+    let mut placements = Vec::new();
+    for &index in indices {
+        let mut best: Option<(Point, &OrientedPart)> = None;
+        for part in &oriented[index] {
+            if let Some(p) = bottom_left_point(&placed, part, width, height) {
+                let better = match best {
+                    None => true,
+                    Some((b, _)) => p.1 < b.1 - EPS || (p.1 < b.1 + EPS && p.0 < b.0 - EPS),
+                };
+                if better {
+                    best = Some((p, part));
+                }
+            }
+        }
+        if let Some((p, part)) = best {
+            placed.push((part, p));
+            placements.push(NestPlacement {
+                part_index: index,
+                bin_id,
+                x: p.0,
+                y: p.1,
+                rotation: part.rotation,
+            });
+        }
+    }
+    placements
+}
@@ -1,4 +1,15 @@
-use crate::{Rectangle, bottom_left_placement, maxrects_placement, skyline_placement, guillotine_placement};
+use crate::{Rectangle, Position, Rotation, BinSizeConstraints, Gravity, compact_with_kerf, Objective, PackConfig, lower_bounds, StageLimit, cut_tree, CutNode, find_remnants, Remnant, cutting_stock_with_kerf, CuttingPlan, RemnantStore, StoredRemnant};
+use crate::portfolio::best_of;
+use crate::bin_search::search_size;
+use crate::remnant_store::normalize_material;
+
+use std::collections::{HashMap, HashSet};
+use std::thread;
+use crate::bottom_left::bottom_left_placement_in;
+use crate::maxrects::maxrects_placement_in;
+use crate::skyline::skyline_placement_in;
+use crate::guillotine::guillotine_placement_in;
+use crate::staged::staged_placement_in;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PackAlgorithm {
@@ -13,6 +24,123 @@ pub enum PackAlgorithm {
     GuillotineBssfSas, // Best Short Side Fit - Shorter Axis Split
 }
 
+impl PackAlgorithm {
+    pub const ALL: [PackAlgorithm; 9] = [
+        PackAlgorithm::BottomLeft,
+        PackAlgorithm::MaxRects,
+        PackAlgorithm::MaxRectsBssf,
+        PackAlgorithm::MaxRectsBaf,
+        PackAlgorithm::MaxRectsBlsf,
+        PackAlgorithm::Skyline,
+        PackAlgorithm::SkylineBl,
+        PackAlgorithm::Guillotine,
+        PackAlgorithm::GuillotineBssfSas,
+    ];
+
+    /// 同じ配置関数を使うアルゴリズムの代表（ALLの中で最初のもの）
+    pub fn placement(&self) -> PackAlgorithm {
+        match self {
+            PackAlgorithm::BottomLeft => PackAlgorithm::BottomLeft,
+            PackAlgorithm::MaxRects |
+            PackAlgorithm::MaxRectsBssf |
+            PackAlgorithm::MaxRectsBaf |
+            PackAlgorithm::MaxRectsBlsf => PackAlgorithm::MaxRects,
+            PackAlgorithm::Skyline |
+            PackAlgorithm::SkylineBl => PackAlgorithm::Skyline,
+            PackAlgorithm::Guillotine |
+            PackAlgorithm::GuillotineBssfSas => PackAlgorithm::Guillotine,
+        }
+    }
+
+    /// 選択されたアルゴリズムで1つのビンに配置する
+    pub fn place(&self, rectangles: &[Rectangle], container_width: u32, container_height: u32, allow_rotate: bool) -> Vec<(Rectangle, Position, bool)> {
+        self.place_in(rectangles, container_width, Some(container_height), Rotation::All(allow_rotate))
+    }
+
+    /// 高さを省略した場合は上方向に制限のないストリップとして配置する
+    pub(crate) fn place_in(&self, rectangles: &[Rectangle], container_width: u32, container_height: Option<u32>, rotation: Rotation) -> Vec<(Rectangle, Position, bool)> {
+        match self.placement() {
+            PackAlgorithm::BottomLeft => {
+                bottom_left_placement_in(rectangles, container_width, container_height, rotation)
+            }
+            PackAlgorithm::Skyline => {
+                skyline_placement_in(rectangles, container_width, container_height, rotation)
+            }
+            PackAlgorithm::Guillotine => {
+                guillotine_placement_in(rectangles, container_width, container_height, rotation, 0)
+            }
+            _ => {
+                maxrects_placement_in(rectangles, container_width, container_height, rotation)
+            }
+        }
+    }
+
+    /// 矩形同士の間にkerf（刃の厚み）分の隙間を空けて配置する
+    pub(crate) fn place_with_kerf(&self, rectangles: &[Rectangle], container_width: u32, container_height: Option<u32>, rotation: Rotation, kerf: u32) -> Vec<(Rectangle, Position, bool)> {
+        if kerf == 0 {
+            return self.place_in(rectangles, container_width, container_height, rotation);
+        }
+        if matches!(self, PackAlgorithm::Guillotine | PackAlgorithm::GuillotineBssfSas) {
+            return guillotine_placement_in(rectangles, container_width, container_height, rotation, kerf);
+        }
+        // 矩形とビンをkerf分だけ広げると、矩形同士の間にだけkerfが入る
+        let inflated: Vec<Rectangle> = rectangles
+            .iter()
+            .map(|r| Rectangle { width: r.width + kerf, height: r.height + kerf })
+            .collect();
+        self.place_in(&inflated, container_width + kerf, container_height.map(|h| h + kerf), rotation)
+            .into_iter()
+            .map(|(r, pos, rotated)| (Rectangle { width: r.width - kerf, height: r.height - kerf }, pos, rotated))
+            .collect()
+    }
+}
+
+/// 配置前に矩形を並べ替える順序（いずれも降順、同値なら追加順）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /// 追加順のまま
+    None,
+    Area,
+    Perimeter,
+    LongSide,
+    ShortSide,
+    Width,
+    Height,
+}
+
+impl SortOrder {
+    pub const ALL: [SortOrder; 7] = [
+        SortOrder::None,
+        SortOrder::Area,
+        SortOrder::Perimeter,
+        SortOrder::LongSide,
+        SortOrder::ShortSide,
+        SortOrder::Width,
+        SortOrder::Height,
+    ];
+
+    fn key(&self, rect: &Rectangle) -> u64 {
+        let (w, h) = (rect.width as u64, rect.height as u64);
+        match self {
+            SortOrder::None => 0,
+            SortOrder::Area => w * h,
+            SortOrder::Perimeter => w + h,
+            SortOrder::LongSide => w.max(h),
+            SortOrder::ShortSide => w.min(h),
+            SortOrder::Width => w,
+            SortOrder::Height => h,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupMode {
+    /// グループ全体を1つのビンに配置するか、まったく配置しない
+    Required,
+    /// 可能な限り同じビンにまとめるが、入りきらない場合は分割を許す
+    Preferred,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Bin {
     pub width: u32,
@@ -27,39 +155,377 @@ pub struct PackedRect {
     pub height: u32,
     pub rotated: bool,
     pub bin_id: usize,
+    /// `add_rect` が返した矩形のID
+    pub rect_id: usize,
+    /// 保管庫の端材に配置されたか
+    pub in_remnant: bool,
+}
+
+/// 木目の方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grain {
+    /// 幅の方向
+    Horizontal,
+    /// 高さの方向
+    Vertical,
+}
+
+/// 使用範囲の切り上げ方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeRounding {
+    Exact,
+    PowerOfTwo,
+    MultipleOf(u32),
+}
+
+impl SizeRounding {
+    fn round_up(&self, value: u32) -> u32 {
+        match *self {
+            SizeRounding::Exact => value,
+            SizeRounding::PowerOfTwo => value.next_power_of_two(),
+            SizeRounding::MultipleOf(n) if n > 0 => value.div_ceil(n) * n,
+            SizeRounding::MultipleOf(_) => value,
+        }
+    }
+}
+
+/// ビンごとの配置結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackedBin {
+    pub bin_id: usize,
+    pub width: u32,
+    pub height: u32,
+    /// 原点から見た使用範囲（切り上げ後、ビンの大きさを超えない）
+    pub used_width: u32,
+    pub used_height: u32,
+    pub rect_count: usize,
+    /// 在庫から取り出したビンの場合、`add_stock` が返した在庫ID
+    pub stock_id: Option<usize>,
+    /// 保管庫の端材の場合、その端材ID
+    pub remnant_id: Option<u64>,
+}
+
+/// 同じ矩形の必要数に対する配置結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DemandReport {
+    pub demand_id: usize,
+    pub rect: Rectangle,
+    pub quantity: usize,
+    pub placed: usize,
+    /// (ビンID, 配置された枚数)
+    pub bins: Vec<(usize, usize)>,
+    /// 各コピーの矩形ID
+    pub rect_ids: Vec<usize>,
+}
+
+/// 素材ごとの配置結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaterialPool {
+    /// 素材のない矩形とビンはNone
+    pub material: Option<String>,
+    pub rects: Vec<PackedRect>,
+    pub bins: Vec<PackedBin>,
+    /// 配置できなかった矩形ID
+    pub unplaced: Vec<usize>,
+}
+
+/// 在庫のビンの種類
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stock {
+    pub width: u32,
+    pub height: u32,
+    /// 使用できる枚数
+    pub quantity: usize,
+    /// 1枚あたりのコスト
+    pub cost: f64,
+}
+
+/// 使用ビン数と下界の比較
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackSummary {
+    pub bins_used: usize,
+    /// 追加したビンと在庫のすべての大きさを含む、最大の幅と高さで計算した必要ビン数の下界。
+    /// 枚数についての下界で、コストの最適性は表さない
+    pub lower_bound: usize,
+    /// 配置できなかった矩形の数
+    pub unplaced: usize,
+}
+
+impl PackSummary {
+    /// すべて配置され、使用ビン数が下界に一致していれば、ビン数について最適であることが証明されている
+    pub fn is_optimal(&self) -> bool {
+        self.unplaced == 0 && self.bins_used <= self.lower_bound
+    }
+
+    pub fn gap(&self) -> usize {
+        self.bins_used.saturating_sub(self.lower_bound)
+    }
+}
+
+/// 矩形を配置してよいビンの集合
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BinFilter {
+    /// `add_bin` が返したビンIDで指定
+    Ids(Vec<usize>),
+    /// `add_bin_tag` で付けたタグのいずれかを持つビン
+    Tags(Vec<String>),
 }
 
+/// 素材ごとに分けて配置する際の受け持ち
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Pool {
+    material: Option<String>,
+    /// 配置しないグループ
+    excluded_groups: HashSet<usize>,
+}
+
+/// 1つのビンの配置結果（矩形ID, 位置, 回転）
+type Layout = Vec<(usize, Position, bool)>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Item {
+    rect: Rectangle,
+    group: Option<usize>,
+    allowed_bins: Option<BinFilter>,
+    grain: Option<Grain>,
+    /// `add_demand` で追加したコピーの場合、その必要数のID
+    demand: Option<usize>,
+    material: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BinSlot {
+    bin: Bin,
+    tags: Vec<String>,
+    grain: Option<Grain>,
+    /// 在庫から取り出したビンの在庫ID
+    stock: Option<usize>,
+    /// 保管庫から出した端材のID
+    remnant: Option<u64>,
+    material: Option<String>,
+}
+
+#[derive(Debug, Clone)]
 pub struct Packer {
-    rects: Vec<Rectangle>,
-    bins: Vec<Bin>,
+    items: Vec<Item>,
+    bins: Vec<BinSlot>,
+    stock: Vec<Stock>,
+    /// 在庫の種類ごとの素材
+    stock_materials: Vec<Option<String>>,
+    /// 在庫の種類ごとの木目
+    stock_grains: Vec<Option<Grain>>,
+    /// 素材ごとに分けて配置する場合、このPackerが受け持つプール
+    pool: Option<Pool>,
+    /// 次の実行で優先して使う保管庫の端材
+    remnant_offers: Vec<StoredRemnant>,
+    /// 必要数ごとの(矩形, コピーの矩形ID)
+    demands: Vec<(Rectangle, Vec<usize>)>,
     algorithm: PackAlgorithm,
     allow_rotation: bool,
+    group_mode: GroupMode,
+    sort_order: SortOrder,
+    size_rounding: SizeRounding,
+    kerf: u32,
+    stage_limit: Option<StageLimit>,
     packed_rects: Vec<PackedRect>,
 }
 
 impl Packer {
     pub fn new() -> Self {
         Self {
-            rects: Vec::new(),
+            items: Vec::new(),
             bins: Vec::new(),
+            stock: Vec::new(),
+            stock_materials: Vec::new(),
+            stock_grains: Vec::new(),
+            pool: None,
+            remnant_offers: Vec::new(),
+            demands: Vec::new(),
             algorithm: PackAlgorithm::MaxRects,
             allow_rotation: false,
+            group_mode: GroupMode::Required,
+            sort_order: SortOrder::None,
+            size_rounding: SizeRounding::Exact,
+            kerf: 0,
+            stage_limit: None,
             packed_rects: Vec::new(),
         }
     }
 
-    pub fn add_rect(&mut self, rect: Rectangle) {
-        self.rects.push(rect);
+    pub fn add_rect(&mut self, rect: Rectangle) -> usize {
+        self.items.push(Item { rect, group: None, allowed_bins: None, grain: None, demand: None, material: None });
+        self.items.len() - 1
+    }
+
+    /// 同じ矩形を必要数だけ追加し、必要数のIDを返す。
+    /// 各コピーには通常の矩形IDが連番で振られる
+    pub fn add_demand(&mut self, rect: Rectangle, quantity: usize) -> usize {
+        let demand_id = self.demands.len();
+        let rect_ids = (0..quantity)
+            .map(|_| {
+                let rect_id = self.add_rect(rect);
+                self.items[rect_id].demand = Some(demand_id);
+                rect_id
+            })
+            .collect();
+        self.demands.push((rect, rect_ids));
+        demand_id
+    }
+
+    /// 必要数ごとに、何枚がどのビンに配置されたかを返す
+    pub fn demand_report(&self) -> Vec<DemandReport> {
+        self.demands
+            .iter()
+            .enumerate()
+            .map(|(demand_id, (rect, rect_ids))| {
+                let mut bins: Vec<(usize, usize)> = Vec::new();
+                for packed in self.packed_rects.iter().filter(|r| self.items[r.rect_id].demand == Some(demand_id)) {
+                    match bins.iter_mut().find(|(bin_id, _)| *bin_id == packed.bin_id) {
+                        Some((_, count)) => *count += 1,
+                        None => bins.push((packed.bin_id, 1)),
+                    }
+                }
+                bins.sort_unstable();
+                DemandReport {
+                    demand_id,
+                    rect: *rect,
+                    quantity: rect_ids.len(),
+                    placed: bins.iter().map(|&(_, count)| count).sum(),
+                    bins,
+                    rect_ids: rect_ids.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// 同じビンに配置すべきグループを指定して矩形を追加する
+    pub fn add_rect_to_group(&mut self, rect: Rectangle, group: usize) -> usize {
+        self.items.push(Item { rect, group: Some(group), allowed_bins: None, grain: None, demand: None, material: None });
+        self.items.len() - 1
+    }
+
+    pub fn set_group_mode(&mut self, mode: GroupMode) {
+        self.group_mode = mode;
+    }
+
+    pub fn set_size_rounding(&mut self, rounding: SizeRounding) {
+        self.size_rounding = rounding;
+    }
+
+    /// 切断1回ごとに失われる幅を設定する。
+    /// Guillotine系では切断ごとに空き領域から取り除き、それ以外では矩形同士の間隔として扱う
+    pub fn set_kerf(&mut self, kerf: u32) {
+        self.kerf = kerf;
+    }
+
+    /// Guillotine系アルゴリズムの切断段数を制限する。Noneなら制限なし
+    pub fn set_guillotine_stages(&mut self, limit: Option<StageLimit>) {
+        self.stage_limit = limit;
+    }
+
+    /// 矩形を配置してよいビンを制限する
+    pub fn set_allowed_bins(&mut self, rect_id: usize, filter: BinFilter) {
+        self.items[rect_id].allowed_bins = Some(filter);
+    }
+
+    /// 矩形を1つのビンに固定する
+    pub fn pin_to_bin(&mut self, rect_id: usize, bin_id: usize) {
+        self.set_allowed_bins(rect_id, BinFilter::Ids(vec![bin_id]));
+    }
+
+    /// ビンを追加してIDを返す。追加したビンは常に先頭に並び、在庫や保管庫から取り出したビンは
+    /// その後ろに置かれるので、`pack` の前後でIDは変わらない
+    pub fn add_bin(&mut self, width: u32, height: u32) -> usize {
+        let bin_id = self.bins.iter().take_while(|slot| slot.stock.is_none() && slot.remnant.is_none()).count();
+        self.bins.insert(bin_id, BinSlot { bin: Bin { width, height }, tags: Vec::new(), grain: None, stock: None, remnant: None, material: None });
+        // 前回の結果で後ろにずれたビンのIDを合わせる
+        for packed in &mut self.packed_rects {
+            if packed.bin_id >= bin_id {
+                packed.bin_id += 1;
+            }
+        }
+        bin_id
+    }
+
+    /// 在庫のビンの種類を追加し、在庫IDを返す。
+    /// `add_bin` のビンを使い切った後、総コストが小さくなるように在庫から取り出す
+    pub fn add_stock(&mut self, width: u32, height: u32, quantity: usize, cost: f64) -> usize {
+        self.stock.push(Stock { width, height, quantity, cost });
+        self.stock_materials.push(None);
+        self.stock_grains.push(None);
+        self.stock.len() - 1
+    }
+
+    /// 使用した在庫のビンのコストの合計
+    pub fn total_cost(&self) -> f64 {
+        self.bin_list()
+            .iter()
+            .filter_map(|b| b.stock_id)
+            .map(|stock_id| self.stock[stock_id].cost)
+            .sum()
+    }
+
+    /// 矩形の木目の方向を設定する。ビンにも木目がある場合、
+    /// 回転の設定に関わらず木目が揃う向きにだけ配置される
+    pub fn set_rect_grain(&mut self, rect_id: usize, grain: Grain) {
+        self.items[rect_id].grain = Some(grain);
+    }
+
+    pub fn set_bin_grain(&mut self, bin_id: usize, grain: Grain) {
+        self.bins[bin_id].grain = Some(grain);
+    }
+
+    pub fn set_stock_grain(&mut self, stock_id: usize, grain: Grain) {
+        self.stock_grains[stock_id] = Some(grain);
+    }
+
+    /// 素材を設定する。矩形は同じ素材のビンにだけ配置される（素材のない矩形は素材のないビンだけ）
+    /// 素材名の空白は1つに揃え、空の素材は素材なしとして扱う
+    pub fn set_rect_material(&mut self, rect_id: usize, material: &str) {
+        self.items[rect_id].material = normalize_material(material);
+    }
+
+    /// 必要数のすべてのコピーに素材を設定する
+    pub fn set_demand_material(&mut self, demand_id: usize, material: &str) {
+        for rect_id in self.demands[demand_id].1.clone() {
+            self.set_rect_material(rect_id, material);
+        }
+    }
+
+    pub fn set_bin_material(&mut self, bin_id: usize, material: &str) {
+        self.bins[bin_id].material = normalize_material(material);
+    }
+
+    pub fn set_stock_material(&mut self, stock_id: usize, material: &str) {
+        self.stock_materials[stock_id] = normalize_material(material);
+    }
+
+    /// 矩形・ビン・在庫に現れる素材の一覧（素材なしはNoneとして先頭）
+    pub fn materials(&self) -> Vec<Option<String>> {
+        let mut materials: Vec<Option<String>> = self
+            .items
+            .iter()
+            .map(|item| item.material.clone())
+            .chain(self.bins.iter().map(|slot| slot.material.clone()))
+            .chain(self.stock_materials.iter().cloned())
+            .collect();
+        materials.sort();
+        materials.dedup();
+        materials
     }
 
-    pub fn add_bin(&mut self, width: u32, height: u32) {
-        self.bins.push(Bin { width, height });
+    pub fn add_bin_tag(&mut self, bin_id: usize, tag: &str) {
+        self.bins[bin_id].tags.push(tag.to_string());
     }
 
     pub fn set_pack_algo(&mut self, algo: PackAlgorithm) {
         self.algorithm = algo;
     }
 
+    pub fn set_sort_order(&mut self, order: SortOrder) {
+        self.sort_order = order;
+    }
+
     pub fn enable_rotation(&mut self) {
         self.allow_rotation = true;
     }
@@ -70,42 +536,76 @@ impl Packer {
 
     pub fn pack(&mut self) {
         self.packed_rects.clear();
-        
-        let mut remaining_rects = self.rects.clone();
-        
-        for (bin_id, bin) in self.bins.iter().enumerate() {
-            if remaining_rects.is_empty() {
+        // 前回の実行で在庫や保管庫から取り出したビンを戻す
+        self.bins.retain(|slot| slot.stock.is_none() && slot.remnant.is_none());
+        let first_remnant = self.bins.len();
+        for remnant in &self.remnant_offers {
+            self.bins.push(BinSlot {
+                bin: Bin { width: remnant.width, height: remnant.height },
+                tags: Vec::new(),
+                grain: remnant.grain,
+                stock: None,
+                remnant: Some(remnant.id),
+                material: remnant.material.clone(),
+            });
+        }
+
+        let mut remaining: Vec<usize> = (0..self.items.len())
+            .filter(|&i| {
+                self.pool.as_ref().is_none_or(|pool| {
+                    let item = &self.items[i];
+                    item.material == pool.material && !item.group.is_some_and(|g| pool.excluded_groups.contains(&g))
+                })
+            })
+            .collect();
+        remaining.sort_by_key(|&i| std::cmp::Reverse(self.sort_order.key(&self.items[i].rect)));
+        let mut layouts: Vec<Layout> = vec![Vec::new(); self.bins.len()];
+
+        // 保管庫の端材を先に埋める
+        let order = (first_remnant..self.bins.len()).chain(0..first_remnant);
+        for bin_id in order {
+            if remaining.is_empty() {
                 break;
             }
-            
-            let placed = match self.algorithm {
-                PackAlgorithm::BottomLeft => {
-                    bottom_left_placement(&remaining_rects, bin.width, bin.height, self.allow_rotation)
-                }
-                PackAlgorithm::MaxRects | 
-                PackAlgorithm::MaxRectsBssf | 
-                PackAlgorithm::MaxRectsBaf | 
-                PackAlgorithm::MaxRectsBlsf => {
-                    maxrects_placement(&remaining_rects, bin.width, bin.height, self.allow_rotation)
-                }
-                PackAlgorithm::Skyline | 
-                PackAlgorithm::SkylineBl => {
-                    skyline_placement(&remaining_rects, bin.width, bin.height, self.allow_rotation)
+            let placed = self.fill_bin(bin_id, &remaining);
+            remove_placed(&mut remaining, &placed);
+            layouts[bin_id] = placed;
+        }
+        if !self.stock.is_empty() {
+            self.fill_from_stock(&mut remaining, &mut layouts);
+        }
+
+        // Preferredモードではまとめて入らなかったグループを分割して空きに詰める
+        if self.group_mode == GroupMode::Preferred {
+            for (bin_id, layout) in layouts.iter_mut().enumerate() {
+                if remaining.is_empty() {
+                    break;
                 }
-                PackAlgorithm::Guillotine | 
-                PackAlgorithm::GuillotineBssfSas => {
-                    guillotine_placement(&remaining_rects, bin.width, bin.height, self.allow_rotation)
+                // 配置済みの矩形を先頭に置く。入力順に詰める配置ではその配置は変わらないが、
+                // 内部で並べ替える配置（段数制限など）では配置済みの矩形が落ちることがあるので、
+                // その場合は再配置を採用しない
+                let mut ids: Vec<usize> = layout.iter().map(|&(id, _, _)| id).collect();
+                ids.extend(remaining.iter().copied().filter(|&i| self.is_allowed(i, bin_id)));
+                let placed = self.place_items(bin_id, &ids);
+                let placed_ids: HashSet<usize> = placed.iter().map(|&(id, _, _)| id).collect();
+                if !layout.iter().all(|(id, _, _)| placed_ids.contains(id)) {
+                    continue;
                 }
-            };
-            
-            // 配置された矩形をPackedRectに変換
-            for &(orig_rect, pos, rotated) in placed.iter() {
+                remove_placed(&mut remaining, &placed);
+                *layout = placed;
+            }
+        }
+
+        // 配置された矩形をPackedRectに変換
+        for (bin_id, layout) in layouts.into_iter().enumerate() {
+            for (rect_id, pos, rotated) in layout {
+                let orig_rect = self.items[rect_id].rect;
                 let (width, height) = if rotated {
                     (orig_rect.height, orig_rect.width)
                 } else {
                     (orig_rect.width, orig_rect.height)
                 };
-                
+
                 self.packed_rects.push(PackedRect {
                     x: pos.x,
                     y: pos.y,
@@ -113,23 +613,477 @@ impl Packer {
                     height,
                     rotated,
                     bin_id,
+                    rect_id,
+                    in_remnant: self.bins[bin_id].remnant.is_some(),
                 });
-                
-                // 配置された矩形を残りのリストから削除
-                if let Some(rect_idx) = remaining_rects.iter().position(|&r| r == orig_rect) {
-                    remaining_rects.remove(rect_idx);
+            }
+        }
+    }
+
+    /// 素材ごとのプールをスレッドに分けて独立に配置し、素材ごとの結果を返す。
+    /// 結果は `pack` と同じく `rect_list` と `bin_list` にも反映される
+    pub fn pack_by_material(&mut self) -> Vec<MaterialPool> {
+        let materials = self.materials();
+        // Requiredモードでは素材の異なるメンバーを持つグループはどのプールにも入りきらないので、
+        // fill_binが入れられないグループを除くのと同様に丸ごと除外する
+        let mut excluded_groups: HashSet<usize> = HashSet::new();
+        if self.group_mode == GroupMode::Required {
+            let mut group_materials: HashMap<usize, &Option<String>> = HashMap::new();
+            for item in &self.items {
+                let Some(group) = item.group else { continue };
+                if *group_materials.entry(group).or_insert(&item.material) != &item.material {
+                    excluded_groups.insert(group);
+                }
+            }
+        }
+        let pools: Vec<Packer> = thread::scope(|scope| {
+            let handles: Vec<_> = materials
+                .iter()
+                .map(|material| {
+                    let mut pool = self.clone();
+                    pool.pool = Some(Pool { material: material.clone(), excluded_groups: excluded_groups.clone() });
+                    scope.spawn(move || {
+                        pool.pack();
+                        pool
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        let Some(first) = pools.first() else {
+            self.pack();
+            return Vec::new();
+        };
+
+        // 在庫から取り出したビンは末尾にあるので、プールごとにIDをずらして並べる
+        let base = first.bins.iter().take_while(|slot| slot.stock.is_none()).count();
+        self.bins = first.bins[..base].to_vec();
+        self.packed_rects.clear();
+        for pool in &pools {
+            let offset = self.bins.len() - base;
+            self.bins.extend_from_slice(&pool.bins[base..]);
+            self.packed_rects.extend(pool.packed_rects.iter().map(|r| PackedRect {
+                bin_id: if r.bin_id >= base { r.bin_id + offset } else { r.bin_id },
+                ..*r
+            }));
+        }
+        self.packed_rects.sort_by_key(|r| r.bin_id);
+
+        let bins = self.bin_list();
+        materials
+            .into_iter()
+            .map(|material| {
+                let rects: Vec<PackedRect> = self
+                    .packed_rects
+                    .iter()
+                    .filter(|r| self.items[r.rect_id].material == material)
+                    .copied()
+                    .collect();
+                let unplaced = (0..self.items.len())
+                    .filter(|&i| self.items[i].material == material && !rects.iter().any(|r| r.rect_id == i))
+                    .collect();
+                MaterialPool {
+                    bins: bins.iter().filter(|b| self.bins[b.bin_id].material == material).copied().collect(),
+                    material,
+                    rects,
+                    unplaced,
+                }
+            })
+            .collect()
+    }
+
+    /// すべての矩形が入る最小のビンサイズを探索して配置する。
+    /// 各候補サイズはkerf・グループ・ビン指定・木目・ソート順を含めて `pack` と同じ規則で配置し、
+    /// すべての矩形が配置できたサイズだけを採用する。見つかった場合、追加済みのビンは
+    /// 最初のビンのタグ・木目・素材を引き継いだそのサイズのビン1つに置き換えられる
+    pub fn pack_auto_size(&mut self, constraints: &BinSizeConstraints) -> Option<Bin> {
+        let template = self
+            .bins
+            .iter()
+            .find(|slot| slot.stock.is_none() && slot.remnant.is_none())
+            .cloned()
+            .unwrap_or(BinSlot { bin: Bin { width: 0, height: 0 }, tags: Vec::new(), grain: None, stock: None, remnant: None, material: None });
+        let mut trial = self.clone();
+        trial.stock.clear();
+        trial.stock_materials.clear();
+        trial.stock_grains.clear();
+        trial.remnant_offers.clear();
+        let fits = |width: u32, height: u32| {
+            let mut candidate = trial.clone();
+            candidate.bins = vec![BinSlot { bin: Bin { width, height }, ..template.clone() }];
+            candidate.pack();
+            (candidate.packed_rects.len() == candidate.items.len()).then_some(candidate)
+        };
+        let rects: Vec<Rectangle> = self.items.iter().map(|item| item.rect).collect();
+        // 木目で回転させる矩形があるので、探索範囲は回転ありの緩和で求める
+        let rotate = self.allow_rotation || self.items.iter().any(|item| item.grain.is_some());
+        let slack = self.kerf.saturating_mul(rects.len() as u32);
+        let (_, _, packed) = search_size(&rects, rotate, slack, constraints, fits)?;
+        self.bins = packed.bins;
+        self.packed_rects = packed.packed_rects;
+        Some(self.bins[0].bin)
+    }
+
+    /// アルゴリズム・ソート順・回転の全組み合わせを並列に試し、最良の結果を採用する。
+    /// Packerの設定は勝った組み合わせに置き換わる
+    pub fn pack_best(&mut self, objective: Objective) -> PackConfig {
+        let (winner, config) = best_of(self, objective);
+        *self = winner;
+        config
+    }
+
+    /// 配置結果を指定方向に詰める。矩形同士の間にはkerf分の隙間を残す
+    pub fn compact(&mut self, gravity: Gravity) {
+        compact_with_kerf(&mut self.packed_rects, gravity, self.kerf);
+    }
+
+    /// グループ制約とビン制約を守りながら1つのビンに配置する
+    fn fill_bin(&self, bin_id: usize, candidates: &[usize]) -> Vec<(usize, Position, bool)> {
+        // このビンに入れられないメンバーを含むグループは丸ごと除外する
+        let excluded_groups: Vec<usize> = candidates
+            .iter()
+            .filter(|&&i| !self.is_allowed(i, bin_id))
+            .filter_map(|&i| self.items[i].group)
+            .collect();
+        let mut candidates: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|&i| self.is_allowed(i, bin_id))
+            .filter(|&i| !self.items[i].group.is_some_and(|g| excluded_groups.contains(&g)))
+            .collect();
+        loop {
+            let placed = self.place_items(bin_id, &candidates);
+            // 一部だけ配置されたグループはこのビンの候補から外して再配置する
+            let placed_ids: HashSet<usize> = placed.iter().map(|&(id, _, _)| id).collect();
+            let placed_groups: HashSet<usize> = placed.iter().filter_map(|&(id, _, _)| self.items[id].group).collect();
+            let split_groups: HashSet<usize> = candidates
+                .iter()
+                .filter(|i| !placed_ids.contains(i))
+                .filter_map(|&i| self.items[i].group)
+                .filter(|g| placed_groups.contains(g))
+                .collect();
+            if split_groups.is_empty() {
+                return placed;
+            }
+            candidates.retain(|&i| !self.items[i].group.is_some_and(|g| split_groups.contains(&g)));
+        }
+    }
+
+    /// 在庫を1枚ずつ取り出して詰める。各候補について残りを貪欲に詰めた場合の
+    /// 総コストを見積もり、最も安くなる在庫を選ぶ
+    fn fill_from_stock(&mut self, remaining: &mut Vec<usize>, layouts: &mut Vec<Layout>) {
+        let mut used = vec![0; self.stock.len()];
+        while !remaining.is_empty() {
+            let bin_id = self.bins.len();
+            // (未配置の数, 総コスト, 在庫ID, 配置)
+            let mut best: Option<(usize, f64, usize, Layout)> = None;
+            for stock_id in self.available_stock(&used) {
+                self.bins.push(self.stock_slot(stock_id));
+                let placed = self.fill_bin(bin_id, remaining);
+                self.bins.pop();
+                if placed.is_empty() {
+                    continue;
+                }
+                let mut rest = remaining.clone();
+                remove_placed(&mut rest, &placed);
+                let mut rest_used = used.clone();
+                rest_used[stock_id] += 1;
+                let (unplaced, rest_cost) = self.greedy_stock_cost(rest, rest_used);
+                let cost = self.stock[stock_id].cost + rest_cost;
+                if best.as_ref().is_none_or(|&(u, c, _, _)| (unplaced, cost) < (u, c)) {
+                    best = Some((unplaced, cost, stock_id, placed));
+                }
+            }
+            let Some((_, _, stock_id, placed)) = best else { break };
+            used[stock_id] += 1;
+            self.bins.push(self.stock_slot(stock_id));
+            remove_placed(remaining, &placed);
+            layouts.push(placed);
+        }
+
+        // 中身がより安い在庫に収まるなら取り替える
+        for (bin_id, layout) in layouts.iter_mut().enumerate() {
+            let Some(current) = self.bins[bin_id].stock else { continue };
+            let ids: Vec<usize> = layout.iter().map(|&(id, _, _)| id).collect();
+            let mut cheaper: Vec<usize> = self
+                .available_stock(&used)
+                .into_iter()
+                .filter(|&s| self.stock[s].cost < self.stock[current].cost)
+                .collect();
+            cheaper.sort_by(|&a, &b| self.stock[a].cost.total_cmp(&self.stock[b].cost));
+            for stock_id in cheaper {
+                self.bins[bin_id] = self.stock_slot(stock_id);
+                if !ids.iter().all(|&i| self.is_allowed(i, bin_id)) {
+                    self.bins[bin_id] = self.stock_slot(current);
+                    continue;
+                }
+                let placed = self.place_items(bin_id, &ids);
+                if placed.len() == ids.len() {
+                    used[current] -= 1;
+                    used[stock_id] += 1;
+                    *layout = placed;
+                    break;
+                }
+                self.bins[bin_id] = self.stock_slot(current);
+            }
+        }
+    }
+
+    /// 配置面積あたりのコストが最も小さい在庫を順に使った場合の(未配置の数, 総コスト)
+    fn greedy_stock_cost(&mut self, mut remaining: Vec<usize>, mut used: Vec<usize>) -> (usize, f64) {
+        let base = self.bins.len();
+        let mut cost = 0.0;
+        while !remaining.is_empty() {
+            let bin_id = self.bins.len();
+            let mut best: Option<(f64, usize, Layout)> = None;
+            for stock_id in self.available_stock(&used) {
+                self.bins.push(self.stock_slot(stock_id));
+                let placed = self.fill_bin(bin_id, &remaining);
+                self.bins.pop();
+                let placed_area: u64 = placed
+                    .iter()
+                    .map(|&(id, _, _)| self.items[id].rect.width as u64 * self.items[id].rect.height as u64)
+                    .sum();
+                if placed_area == 0 {
+                    continue;
+                }
+                let cost_per_area = self.stock[stock_id].cost / placed_area as f64;
+                if best.as_ref().is_none_or(|&(c, _, _)| cost_per_area < c) {
+                    best = Some((cost_per_area, stock_id, placed));
                 }
             }
+            let Some((_, stock_id, placed)) = best else { break };
+            used[stock_id] += 1;
+            cost += self.stock[stock_id].cost;
+            // 後続のビンIDをずらさないよう、見積もり中も取り出したビンを並べておく
+            self.bins.push(self.stock_slot(stock_id));
+            remove_placed(&mut remaining, &placed);
         }
+        self.bins.truncate(base);
+        (remaining.len(), cost)
+    }
+
+    /// まだ残っている在庫ID
+    fn available_stock(&self, used: &[usize]) -> Vec<usize> {
+        self.stock
+            .iter()
+            .zip(used)
+            .enumerate()
+            .filter(|(_, (stock, &count))| count < stock.quantity)
+            .map(|(stock_id, _)| stock_id)
+            .collect()
+    }
+
+    fn stock_slot(&self, stock_id: usize) -> BinSlot {
+        let stock = self.stock[stock_id];
+        BinSlot {
+            bin: Bin { width: stock.width, height: stock.height },
+            tags: Vec::new(),
+            grain: self.stock_grains[stock_id],
+            stock: Some(stock_id),
+            remnant: None,
+            material: self.stock_materials[stock_id].clone(),
+        }
+    }
+
+    /// 指定した矩形をアルゴリズムで配置し、結果を矩形IDに対応付ける
+    fn place_items(&self, bin_id: usize, ids: &[usize]) -> Vec<(usize, Position, bool)> {
+        let bin = self.bins[bin_id].bin;
+        // 木目の向きが決まる矩形は回転を禁止し、必要なら先に回転させておく
+        let mut rects = Vec::with_capacity(ids.len());
+        let mut rotatable = Vec::with_capacity(ids.len());
+        let mut forced = Vec::with_capacity(ids.len());
+        for &i in ids {
+            let item = &self.items[i];
+            let (rotate, free) = match (item.grain, self.bins[bin_id].grain) {
+                (Some(item_grain), Some(bin_grain)) => (item_grain != bin_grain, false),
+                _ => (false, self.allow_rotation),
+            };
+            rects.push(if rotate { Rectangle { width: item.rect.height, height: item.rect.width } } else { item.rect });
+            rotatable.push(free);
+            forced.push(rotate);
+        }
+        let rotation = Rotation::PerItem(&rotatable);
+        let guillotine = matches!(self.algorithm, PackAlgorithm::Guillotine | PackAlgorithm::GuillotineBssfSas);
+        let placed = if let (true, Some(limit)) = (guillotine, self.stage_limit) {
+            staged_placement_in(&rects, bin.width, bin.height, rotation, &limit, self.kerf)
+        } else {
+            self.algorithm.place_with_kerf(&rects, bin.width, Some(bin.height), rotation, self.kerf)
+        };
+
+        // 同じサイズの矩形は入れ替え可能なので、未使用の最初の一致に割り当てる。
+        // 回転できる矩形は回転した配置のために残しておく。
+        // (幅, 高さ, 回転可否)ごとに入力順のキューを作り、1回の走査で対応付ける
+        let mut queues: HashMap<(u32, u32, bool), Vec<usize>> = HashMap::new();
+        for j in (0..ids.len()).rev() {
+            queues.entry((rects[j].width, rects[j].height, rotatable[j])).or_default().push(j);
+        }
+        placed
+            .into_iter()
+            .filter_map(|(orig_rect, pos, rotated)| {
+                let (w, h) = (orig_rect.width, orig_rect.height);
+                let fixed = if rotated { None } else { queues.get_mut(&(w, h, false)).and_then(Vec::pop) };
+                let j = fixed.or_else(|| queues.get_mut(&(w, h, true)).and_then(Vec::pop))?;
+                Some((ids[j], pos, rotated != forced[j]))
+            })
+            .collect()
+    }
+
+    fn is_allowed(&self, rect_id: usize, bin_id: usize) -> bool {
+        if self.items[rect_id].material != self.bins[bin_id].material {
+            return false;
+        }
+        match &self.items[rect_id].allowed_bins {
+            None => true,
+            Some(BinFilter::Ids(ids)) => ids.contains(&bin_id),
+            Some(BinFilter::Tags(tags)) => self.bins[bin_id].tags.iter().any(|t| tags.contains(t)),
+        }
+    }
+
+    pub fn rect_count(&self) -> usize {
+        self.items.len()
+    }
+
+    /// 使用ビン数と下界を並べて返す
+    pub fn summary(&self) -> PackSummary {
+        // 使わなかった在庫の大きさも、より少ない枚数で済む可能性があるので含める
+        let sizes: Vec<Bin> = self
+            .bins
+            .iter()
+            .map(|slot| slot.bin)
+            .chain(self.stock.iter().filter(|stock| stock.quantity > 0).map(|stock| Bin { width: stock.width, height: stock.height }))
+            .collect();
+        let width = sizes.iter().map(|bin| bin.width).max().unwrap_or(0);
+        let height = sizes.iter().map(|bin| bin.height).max().unwrap_or(0);
+        let rects: Vec<Rectangle> = self.items.iter().map(|item| item.rect).collect();
+        PackSummary {
+            bins_used: self.bin_list().len(),
+            // 木目で回転させる矩形があるので、その場合は回転ありの緩和で計算する
+            lower_bound: lower_bounds(&rects, width, height, self.allow_rotation || self.items.iter().any(|item| item.grain.is_some())).best(),
+            unplaced: self.items.len() - self.packed_rects.len(),
+        }
+    }
+
+    /// ビンの配置から切断木を復元する。部品のインデックスは矩形ID。
+    /// Guillotine切断で切り出せない配置の場合はNone
+    pub fn cut_tree(&self, bin_id: usize) -> Option<CutNode> {
+        let bin = self.bins.get(bin_id)?.bin;
+        let rects: Vec<&PackedRect> = self.packed_rects.iter().filter(|r| r.bin_id == bin_id).collect();
+        let placements: Vec<(Rectangle, Position, bool)> = rects
+            .iter()
+            .map(|r| (Rectangle { width: r.width, height: r.height }, Position { x: r.x, y: r.y }, false))
+            .collect();
+        let mut tree = cut_tree(&placements, bin.width, bin.height, self.kerf)?;
+        tree.map_items(&|i| rects[i].rect_id);
+        Some(tree)
+    }
+
+    /// 使用したビンに残った、最小サイズ以上の端材を返す
+    pub fn remnants(&self, min_width: u32, min_height: u32) -> Vec<Remnant> {
+        let mut remnants = Vec::new();
+        for packed in self.bin_list() {
+            let placements: Vec<(Rectangle, Position, bool)> = self
+                .packed_rects
+                .iter()
+                .filter(|r| r.bin_id == packed.bin_id)
+                .map(|r| (Rectangle { width: r.width, height: r.height }, Position { x: r.x, y: r.y }, false))
+                .collect();
+            remnants.extend(
+                find_remnants(&placements, packed.width, packed.height, min_width, min_height, self.kerf)
+                    .into_iter()
+                    .map(|rect| Remnant { bin_id: packed.bin_id, rect }),
+            );
+        }
+        remnants
+    }
+
+    /// 保管庫の端材を、次の実行で他のビンより先に使うビンとして登録する。
+    /// 端材のビンIDは `add_bin` で追加したビンの後に続く
+    pub fn offer_remnants(&mut self, store: &RemnantStore) {
+        self.remnant_offers = store.remnants().to_vec();
+    }
+
+    /// 使用した端材を保管庫から取り除き、今回の実行で残った端材を切り出したビンの素材と木目付きで追加する。
+    /// 登録した端材は消費済みなので、以降の `pack` では使わない
+    pub fn update_remnant_store(&mut self, store: &mut RemnantStore) {
+        for packed in self.bin_list() {
+            if let Some(id) = packed.remnant_id {
+                store.remove(id);
+            }
+        }
+        let (min_width, min_height) = store.min_size();
+        for remnant in self.remnants(min_width, min_height) {
+            let id = store.add(remnant.rect.width, remnant.rect.height);
+            let slot = &self.bins[remnant.bin_id];
+            if let Some(material) = &slot.material {
+                store.set_material(id, material);
+            }
+            if let Some(grain) = slot.grain {
+                store.set_grain(id, grain);
+            }
+        }
+        self.remnant_offers.clear();
+    }
+
+    /// 保管庫の端材を優先して配置し、結果の端材を保管庫に戻す
+    pub fn pack_with_remnants(&mut self, store: &mut RemnantStore) {
+        self.offer_remnants(store);
+        self.pack();
+        self.update_remnant_store(store);
+    }
+
+    /// 最初のビンをシートとして、カッティングストックの計画を立てる。
+    /// 対象はそのビンと同じ素材の矩形だけで、kerfは考慮するがグループやビンの指定、木目は考慮しない
+    pub fn cutting_plan(&self, tolerance: f64) -> Option<CuttingPlan> {
+        let sheet = self.bins.first()?;
+        let demands: Vec<(Rectangle, usize)> = self
+            .items
+            .iter()
+            .filter(|item| item.material == sheet.material)
+            .map(|item| (item.rect, 1))
+            .collect();
+        Some(cutting_stock_with_kerf(&demands, sheet.bin.width, sheet.bin.height, self.algorithm, self.allow_rotation, tolerance, self.kerf))
     }
 
     pub fn rect_list(&self) -> &[PackedRect] {
         &self.packed_rects
     }
+
+    /// 矩形が配置されたビンと、その使用範囲の一覧
+    pub fn bin_list(&self) -> Vec<PackedBin> {
+        self.bins
+            .iter()
+            .enumerate()
+            .filter_map(|(bin_id, slot)| {
+                let rects: Vec<&PackedRect> = self.packed_rects.iter().filter(|r| r.bin_id == bin_id).collect();
+                if rects.is_empty() {
+                    return None;
+                }
+                let used_width = rects.iter().map(|r| r.x + r.width).max().unwrap_or(0);
+                let used_height = rects.iter().map(|r| r.y + r.height).max().unwrap_or(0);
+                Some(PackedBin {
+                    bin_id,
+                    width: slot.bin.width,
+                    height: slot.bin.height,
+                    used_width: self.size_rounding.round_up(used_width).min(slot.bin.width),
+                    used_height: self.size_rounding.round_up(used_height).min(slot.bin.height),
+                    rect_count: rects.len(),
+                    stock_id: slot.stock,
+                    remnant_id: slot.remnant,
+                })
+            })
+            .collect()
+    }
 }
 
 impl Default for Packer {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+/// 配置された矩形IDを取り除く
+fn remove_placed(remaining: &mut Vec<usize>, placed: &[(usize, Position, bool)]) {
+    let placed: HashSet<usize> = placed.iter().map(|&(id, _, _)| id).collect();
+    remaining.retain(|i| !placed.contains(i));
+}
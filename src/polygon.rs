@@ -0,0 +1,235 @@
+use crate::{Rectangle, Position};
+
+/// 単純多角形で定義されたビン（革の形状や斜めにカットされた端材など）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolygonBin {
+    pub vertices: Vec<Position>,
+}
+
+impl PolygonBin {
+    pub fn new(vertices: Vec<Position>) -> Self {
+        Self { vertices }
+    }
+
+    /// 多角形の外接矩形（左下と右上の頂点）を返す
+    pub fn bounding_box(&self) -> (Position, Position) {
+        let min_x = self.vertices.iter().map(|v| v.x).min().unwrap_or(0);
+        let min_y = self.vertices.iter().map(|v| v.y).min().unwrap_or(0);
+        let max_x = self.vertices.iter().map(|v| v.x).max().unwrap_or(0);
+        let max_y = self.vertices.iter().map(|v| v.y).max().unwrap_or(0);
+        (Position { x: min_x, y: min_y }, Position { x: max_x, y: max_y })
+    }
+
+    /// 矩形が多角形の内部（境界を含む）に完全に収まるかを辺に対して厳密に判定する
+    pub fn contains_rect(&self, pos: Position, width: u32, height: u32) -> bool {
+        if self.vertices.len() < 3 {
+            return false;
+        }
+        // 中心点を整数で扱うため座標を2倍して計算する
+        let x0 = 2 * pos.x as i64;
+        let y0 = 2 * pos.y as i64;
+        let x1 = x0 + 2 * width as i64;
+        let y1 = y0 + 2 * height as i64;
+        let center = (x0 + width as i64, y0 + height as i64);
+
+        if width == 0 || height == 0 {
+            return [(x0, y0), (x1, y1), center]
+                .iter()
+                .all(|&p| self.point_in_closed(p));
+        }
+        // 多角形の辺が矩形の内部を横切っていれば収まらない
+        for (a, b) in self.doubled_edges() {
+            if segment_crosses_open_box(a, b, (x0, y0, x1, y1)) {
+                return false;
+            }
+        }
+        // 辺が内部を横切らない場合、矩形の内部は多角形の内側か外側のどちらかに収まる
+        self.point_strictly_inside(center)
+    }
+
+    fn doubled_edges(&self) -> impl Iterator<Item = ((i64, i64), (i64, i64))> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| {
+            let a = self.vertices[i];
+            let b = self.vertices[(i + 1) % n];
+            (
+                (2 * a.x as i64, 2 * a.y as i64),
+                (2 * b.x as i64, 2 * b.y as i64),
+            )
+        })
+    }
+
+    fn point_strictly_inside(&self, p: (i64, i64)) -> bool {
+        let mut inside = false;
+        for (a, b) in self.doubled_edges() {
+            if (a.1 > p.1) != (b.1 > p.1) {
+                let cross = (b.0 - a.0) * (p.1 - a.1) - (p.0 - a.0) * (b.1 - a.1);
+                if (b.1 > a.1 && cross > 0) || (b.1 < a.1 && cross < 0) {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+
+    fn point_in_closed(&self, p: (i64, i64)) -> bool {
+        let on_boundary = self.doubled_edges().any(|(a, b)| {
+            let cross = (b.0 - a.0) * (p.1 - a.1) - (p.0 - a.0) * (b.1 - a.1);
+            cross == 0
+                && p.0 >= a.0.min(b.0) && p.0 <= a.0.max(b.0)
+                && p.1 >= a.1.min(b.1) && p.1 <= a.1.max(b.1)
+        });
+        on_boundary || self.point_strictly_inside(p)
+    }
+}
+
+/// 線分が開矩形の内部と交差するかを分離軸で判定する
+fn segment_crosses_open_box(a: (i64, i64), b: (i64, i64), bx: (i64, i64, i64, i64)) -> bool {
+    let (x0, y0, x1, y1) = bx;
+    if a.0.max(b.0) <= x0 || a.0.min(b.0) >= x1 {
+        return false;
+    }
+    if a.1.max(b.1) <= y0 || a.1.min(b.1) >= y1 {
+        return false;
+    }
+    // 線分の法線方向: 矩形の角が直線の両側に厳密に分かれていれば交差
+    let dx = b.0 - a.0;
+    let dy = b.1 - a.1;
+    let sides = [(x0, y0), (x1, y0), (x0, y1), (x1, y1)].map(|(cx, cy)| dx * (cy - a.1) - dy * (cx - a.0));
+    sides.iter().any(|&s| s < 0) && sides.iter().any(|&s| s > 0)
+}
+
+/// 辺上で指定した座標を通る点の反対側の座標（切り捨てと切り上げ）を返す
+fn edge_crossings(a: Position, b: Position, value: u32, horizontal: bool) -> Option<(u32, u32)> {
+    let (a_main, a_other, b_main, b_other) = if horizontal {
+        (a.y as i64, a.x as i64, b.y as i64, b.x as i64)
+    } else {
+        (a.x as i64, a.y as i64, b.x as i64, b.y as i64)
+    };
+    let v = value as i64;
+    if a_main == b_main || v < a_main.min(b_main) || v > a_main.max(b_main) {
+        return None;
+    }
+    let num = (v - a_main) * (b_other - a_other);
+    let den = b_main - a_main;
+    let floor = a_other + num.div_euclid(den);
+    let ceil = floor + if num.rem_euclid(den) != 0 { 1 } else { 0 };
+    if ceil < 0 {
+        return None;
+    }
+    Some((floor.max(0) as u32, ceil as u32))
+}
+
+/// 多角形ビンに対してBL法によるアイテム配置を行う関数
+pub fn polygon_bottom_left_placement(rectangles: &[Rectangle], bin: &PolygonBin, allow_rotate: bool) -> Vec<(Rectangle, Position, bool)> {
+    let mut placed_rectangles: Vec<(Rectangle, Position, bool)> = Vec::new();
+    let n = bin.vertices.len();
+    let edges: Vec<(Position, Position)> = (0..n)
+        .map(|i| (bin.vertices[i], bin.vertices[(i + 1) % n]))
+        .collect();
+
+    for &rect in rectangles {
+        let mut candidates = vec![(rect.width, rect.height, false)];
+        if allow_rotate && rect.width != rect.height {
+            candidates.push((rect.height, rect.width, true));
+        }
+        let mut best: Option<(Position, bool)> = None;
+        for &(w, h, rotated) in &candidates {
+            // 頂点と配置済み矩形の辺から候補座標を集める
+            let mut base_xs: Vec<u32> = Vec::new();
+            let mut base_ys: Vec<u32> = Vec::new();
+            for v in &bin.vertices {
+                base_xs.push(v.x);
+                base_ys.push(v.y);
+            }
+            for &(ref placed_rect, placed_pos, placed_rot) in &placed_rectangles {
+                let (pw, ph) = if placed_rot {
+                    (placed_rect.height, placed_rect.width)
+                } else {
+                    (placed_rect.width, placed_rect.height)
+                };
+                base_xs.push(placed_pos.x + pw);
+                base_xs.push(placed_pos.x);
+                base_ys.push(placed_pos.y + ph);
+                base_ys.push(placed_pos.y);
+            }
+            let mut xs: Vec<u32> = Vec::new();
+            let mut ys: Vec<u32> = Vec::new();
+            for &x in &base_xs {
+                xs.push(x);
+                xs.extend(x.checked_sub(w));
+            }
+            for &y in &base_ys {
+                ys.push(y);
+                ys.extend(y.checked_sub(h));
+            }
+            // 斜めの辺に矩形の角が接する座標も候補に加える
+            for &(a, b) in &edges {
+                if a.x == b.x || a.y == b.y {
+                    continue;
+                }
+                for &y in &base_ys {
+                    for line in [y, y + h] {
+                        if let Some((lo, hi)) = edge_crossings(a, b, line, true) {
+                            for x in [lo, hi] {
+                                xs.push(x);
+                                xs.extend(x.checked_sub(w));
+                            }
+                        }
+                    }
+                }
+                for &x in &base_xs {
+                    for line in [x, x + w] {
+                        if let Some((lo, hi)) = edge_crossings(a, b, line, false) {
+                            for y in [lo, hi] {
+                                ys.push(y);
+                                ys.extend(y.checked_sub(h));
+                            }
+                        }
+                    }
+                }
+            }
+            xs.sort_unstable();
+            xs.dedup();
+            ys.sort_unstable();
+            ys.dedup();
+
+            'search: for &y in &ys {
+                if let Some((best_pos, _)) = best {
+                    if y > best_pos.y {
+                        break;
+                    }
+                }
+                for &x in &xs {
+                    let pos = Position { x, y };
+                    if !bin.contains_rect(pos, w, h) {
+                        continue;
+                    }
+                    // 衝突判定
+                    let overlap = placed_rectangles.iter().any(|&(ref placed_rect, placed_pos, placed_rot)| {
+                        let (pw, ph) = if placed_rot {
+                            (placed_rect.height, placed_rect.width)
+                        } else {
+                            (placed_rect.width, placed_rect.height)
+                        };
+                        pos.x < placed_pos.x + pw &&
+                        pos.x + w > placed_pos.x &&
+                        pos.y < placed_pos.y + ph &&
+                        pos.y + h > placed_pos.y
+                    });
+                    if overlap {
+                        continue;
+                    }
+                    if best.is_none() || (y, x) < (best.unwrap().0.y, best.unwrap().0.x) {
+                        best = Some((pos, rotated));
+                    }
+                    break 'search;
+                }
+            }
+        }
+        if let Some((pos, rotated)) = best {
+            placed_rectangles.push((rect, pos, rotated));
+        }
+    }
+    placed_rectangles
+}
@@ -0,0 +1,101 @@
+use std::thread;
+
+use crate::{Packer, PackAlgorithm, PackedBin, SortOrder};
+
+/// ポートフォリオで試す設定の組み合わせ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackConfig {
+    pub algorithm: PackAlgorithm,
+    pub sort_order: SortOrder,
+    pub allow_rotation: bool,
+}
+
+/// 最良の結果を選ぶ基準。どちらも配置できた矩形の数を最優先する
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    /// 使用ビン数、最後のビンの占有率（低いほど良い）、外接矩形の面積の順
+    BinsUsed,
+    /// 外接矩形の面積、使用ビン数の順
+    BoundingArea,
+}
+
+impl PackConfig {
+    /// アルゴリズム・ソート順・回転の有無の全組み合わせ。
+    /// 同じ配置関数を使うアルゴリズムは結果も同じなので代表だけを試す
+    pub fn all() -> Vec<PackConfig> {
+        let mut configs = Vec::new();
+        for algorithm in PackAlgorithm::ALL.into_iter().filter(|a| a.placement() == *a) {
+            for sort_order in SortOrder::ALL {
+                for allow_rotation in [false, true] {
+                    configs.push(PackConfig { algorithm, sort_order, allow_rotation });
+                }
+            }
+        }
+        configs
+    }
+}
+
+/// 小さいほど良いスコア
+type Score = (usize, u64, u64, u64);
+
+fn score(packer: &Packer, objective: Objective) -> Score {
+    let bins: Vec<PackedBin> = packer.bin_list();
+    let unplaced = packer.rect_count() - packer.rect_list().len();
+    let bins_used = bins.len() as u64;
+    let bounding_area: u64 = bins.iter().map(|b| b.used_width as u64 * b.used_height as u64).sum();
+    let last_occupancy = bins.last().map_or(0, |last| {
+        let used: u64 = packer
+            .rect_list()
+            .iter()
+            .filter(|r| r.bin_id == last.bin_id)
+            .map(|r| r.width as u64 * r.height as u64)
+            .sum();
+        used * 1_000_000 / (last.width as u64 * last.height as u64).max(1)
+    });
+    match objective {
+        Objective::BinsUsed => (unplaced, bins_used, last_occupancy, bounding_area),
+        Objective::BoundingArea => (unplaced, bounding_area, bins_used, last_occupancy),
+    }
+}
+
+/// 全設定をスレッドに分けて実行し、最良の結果を持つPackerと設定を返す
+pub(crate) fn best_of(packer: &Packer, objective: Objective) -> (Packer, PackConfig) {
+    let configs = PackConfig::all();
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = configs.len().div_ceil(threads);
+
+    let results: Vec<(usize, Score, Packer)> = thread::scope(|scope| {
+        let handles: Vec<_> = configs
+            .chunks(chunk_size)
+            .enumerate()
+            .map(|(chunk_index, chunk)| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .enumerate()
+                        .map(|(i, config)| {
+                            let mut candidate = packer.clone();
+                            candidate.set_pack_algo(config.algorithm);
+                            candidate.set_sort_order(config.sort_order);
+                            if config.allow_rotation {
+                                candidate.enable_rotation();
+                            } else {
+                                candidate.disable_rotation();
+                            }
+                            candidate.pack();
+                            (chunk_index * chunk_size + i, score(&candidate, objective), candidate)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles.into_iter().flat_map(|h| h.join().unwrap()).collect()
+    });
+
+    // 同点の場合は設定一覧で先のものを選ぶ
+    let (index, _, winner) = results
+        .into_iter()
+        .min_by_key(|(index, score, _)| (*score, *index))
+        .unwrap();
+    (winner, configs[index])
+}
@@ -0,0 +1,150 @@
+use crate::{Rectangle, Position};
+
+/// アイテムの不透明セルを表す占有マスク（行優先）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OccupancyMask {
+    pub width: u32,
+    pub height: u32,
+    cells: Vec<bool>,
+}
+
+impl OccupancyMask {
+    /// セル数が幅×高さと一致しない場合はNone
+    pub fn new(width: u32, height: u32, cells: Vec<bool>) -> Option<Self> {
+        let len = (width as usize).checked_mul(height as usize)?;
+        (cells.len() == len).then_some(Self { width, height, cells })
+    }
+
+    /// 全セルが不透明なマスク（通常の矩形と同じ扱い）
+    pub fn full(width: u32, height: u32) -> Self {
+        Self::from_fn(width, height, |_, _| true)
+    }
+
+    pub fn from_fn(width: u32, height: u32, f: impl Fn(u32, u32) -> bool) -> Self {
+        let mut cells = Vec::with_capacity(width as usize * height as usize);
+        for y in 0..height {
+            for x in 0..width {
+                cells.push(f(x, y));
+            }
+        }
+        Self { width, height, cells }
+    }
+
+    pub fn is_opaque(&self, x: u32, y: u32) -> bool {
+        x < self.width && y < self.height && self.cells[y as usize * self.width as usize + x as usize]
+    }
+
+    /// 90度回転したマスクを返す（幅と高さが入れ替わる）
+    pub fn rotated(&self) -> Self {
+        Self::from_fn(self.height, self.width, |x, y| self.is_opaque(y, self.height - 1 - x))
+    }
+
+    pub fn bounding_rect(&self) -> Rectangle {
+        Rectangle { width: self.width, height: self.height }
+    }
+
+    /// 行ごとの不透明セルの連続区間 (y, 開始x, 終了x)
+    fn opaque_runs(&self) -> Vec<(u32, u32, u32)> {
+        let mut runs = Vec::new();
+        for y in 0..self.height {
+            let mut x = 0;
+            while x < self.width {
+                if !self.is_opaque(x, y) {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                while x < self.width && self.is_opaque(x, y) {
+                    x += 1;
+                }
+                runs.push((y, start, x));
+            }
+        }
+        runs
+    }
+}
+
+/// コンテナの占有状態。行ごとの累積和で区間が空いているかをO(1)で判定する
+struct Grid {
+    width: usize,
+    cells: Vec<bool>,
+    /// 各行の累積占有数（行あたり幅+1個）
+    prefix: Vec<u32>,
+}
+
+impl Grid {
+    fn new(width: u32, height: u32) -> Self {
+        let (width, height) = (width as usize, height as usize);
+        Self { width, cells: vec![false; width * height], prefix: vec![0; (width + 1) * height] }
+    }
+
+    /// 行yの[start, end)に占有セルがあれば、その最も右のx
+    fn last_filled(&self, y: u32, start: u32, end: u32) -> Option<u32> {
+        let row = y as usize * (self.width + 1);
+        if self.prefix[row + end as usize] == self.prefix[row + start as usize] {
+            return None;
+        }
+        let cells = &self.cells[y as usize * self.width..];
+        (start..end).rev().find(|&x| cells[x as usize])
+    }
+
+    fn fill(&mut self, y: u32, start: u32, end: u32) {
+        let offset = y as usize * self.width;
+        self.cells[offset + start as usize..offset + end as usize].fill(true);
+        let row = y as usize * (self.width + 1);
+        for x in 0..self.width {
+            self.prefix[row + x + 1] = self.prefix[row + x] + self.cells[offset + x] as u32;
+        }
+    }
+}
+
+/// 占有マスクを噛み合わせて配置する関数（外接矩形同士の重なりは許し、不透明セルの重なりのみ禁止）
+pub fn mask_placement(masks: &[OccupancyMask], container_width: u32, container_height: u32, allow_rotate: bool) -> Vec<(Rectangle, Position, bool)> {
+    let mut placed_rectangles: Vec<(Rectangle, Position, bool)> = Vec::new();
+    let mut grid = Grid::new(container_width, container_height);
+
+    for mask in masks {
+        let mut candidates = vec![(mask.clone(), false)];
+        if allow_rotate && mask.width != mask.height {
+            candidates.push((mask.rotated(), true));
+        }
+        let mut best: Option<(Position, usize)> = None;
+        let mut best_runs = Vec::new();
+        for (i, (oriented, _)) in candidates.iter().enumerate() {
+            let (w, h) = (oriented.width, oriented.height);
+            if w > container_width || h > container_height {
+                continue;
+            }
+            let runs = oriented.opaque_runs();
+            // BL順に走査して最初に重ならない位置を探す
+            'search: for y in 0..=(container_height - h) {
+                if best.is_some_and(|(b, _)| y > b.y) {
+                    break;
+                }
+                let mut x = 0;
+                while x <= container_width - w {
+                    // 重なったセルを区間が越えるまでxを飛ばす
+                    let blocked = runs
+                        .iter()
+                        .find_map(|&(cy, start, end)| grid.last_filled(y + cy, x + start, x + end).map(|last| last + 1 - start));
+                    if let Some(next) = blocked {
+                        x = next;
+                        continue;
+                    }
+                    if best.is_none_or(|(b, _)| (y, x) < (b.y, b.x)) {
+                        best = Some((Position { x, y }, i));
+                        best_runs = runs;
+                    }
+                    break 'search;
+                }
+            }
+        }
+        if let Some((pos, i)) = best {
+            for (cy, start, end) in best_runs {
+                grid.fill(pos.y + cy, pos.x + start, pos.x + end);
+            }
+            placed_rectangles.push((mask.bounding_rect(), pos, candidates[i].1));
+        }
+    }
+    placed_rectangles
+}
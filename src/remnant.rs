@@ -0,0 +1,61 @@
+use crate::{Rectangle, Position, GuillotineRect, CutOrientation};
+use crate::cut_tree::{Item, cut_positions, sub_pieces, items_inside};
+
+/// ビンに残った再利用可能な端材
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Remnant {
+    pub bin_id: usize,
+    pub rect: GuillotineRect,
+}
+
+/// 配置後の空き領域をGuillotine切断で切り出せる端材に分ける関数。
+/// 回転すれば最小サイズを満たすものも含め、面積の大きい順に返す
+pub fn find_remnants(placements: &[(Rectangle, Position, bool)], container_width: u32, container_height: u32, min_width: u32, min_height: u32, kerf: u32) -> Vec<GuillotineRect> {
+    let items: Vec<Item> = placements
+        .iter()
+        .map(|&(r, p, rotated)| {
+            let (w, h) = if rotated { (r.height, r.width) } else { (r.width, r.height) };
+            (p.x, p.y, w, h)
+        })
+        .collect();
+    let sheet = GuillotineRect { position: Position { x: 0, y: 0 }, width: container_width, height: container_height };
+    let indices: Vec<usize> = (0..items.len()).collect();
+    let mut remnants = Vec::new();
+    collect(&items, sheet, &indices, kerf, (min_width, min_height), &mut remnants);
+    remnants.sort_by_key(|r| (std::cmp::Reverse(r.width as u64 * r.height as u64), r.position.y, r.position.x));
+    remnants
+}
+
+fn collect(items: &[Item], piece: GuillotineRect, indices: &[usize], kerf: u32, min_size: (u32, u32), remnants: &mut Vec<GuillotineRect>) {
+    if indices.is_empty() {
+        let (mw, mh) = min_size;
+        if (piece.width >= mw && piece.height >= mh) || (piece.height >= mw && piece.width >= mh) {
+            remnants.push(piece);
+        }
+        return;
+    }
+    // 空の部材が最も大きくなる向きで切る
+    let mut best: Option<(u64, Vec<GuillotineRect>)> = None;
+    for orientation in [CutOrientation::Horizontal, CutOrientation::Vertical] {
+        let positions = cut_positions(items, piece, indices, kerf, orientation);
+        if positions.is_empty() {
+            continue;
+        }
+        let pieces = sub_pieces(piece, &positions, kerf, orientation);
+        let empty_area = pieces
+            .iter()
+            .filter(|&&p| items_inside(items, indices, p).is_empty())
+            .map(|p| p.width as u64 * p.height as u64)
+            .max()
+            .unwrap_or(0);
+        if best.as_ref().is_none_or(|(area, _)| empty_area > *area) {
+            best = Some((empty_area, pieces));
+        }
+    }
+    // 部品そのもの、またはGuillotine切断できない部材
+    let Some((_, pieces)) = best else { return };
+    for sub in pieces {
+        let inside = items_inside(items, indices, sub);
+        collect(items, sub, &inside, kerf, min_size, remnants);
+    }
+}
@@ -0,0 +1,188 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::Grain;
+
+/// ファイル形式の先頭行。素材と木目のない版1も読み込める
+const HEADER: &str = "rustpack-remnants 2";
+const HEADER_V1: &str = "rustpack-remnants 1";
+
+/// 保管されている端材
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredRemnant {
+    /// 保管庫内で一意なID（削除しても再利用しない）
+    pub id: u64,
+    pub width: u32,
+    pub height: u32,
+    /// 切り出したビンの素材
+    pub material: Option<String>,
+    /// 切り出したビンの木目
+    pub grain: Option<Grain>,
+}
+
+/// ジョブをまたいで再利用する端材の保管庫
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemnantStore {
+    remnants: Vec<StoredRemnant>,
+    next_id: u64,
+    /// 保管する端材の最小サイズ（回転して満たすものも含む）
+    min_width: u32,
+    min_height: u32,
+}
+
+impl RemnantStore {
+    pub fn new(min_width: u32, min_height: u32) -> Self {
+        Self { remnants: Vec::new(), next_id: 0, min_width, min_height }
+    }
+
+    pub fn min_size(&self) -> (u32, u32) {
+        (self.min_width, self.min_height)
+    }
+
+    /// 端材を追加してIDを返す
+    pub fn add(&mut self, width: u32, height: u32) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.remnants.push(StoredRemnant { id, width, height, material: None, grain: None });
+        id
+    }
+
+    /// 素材を設定する。空白は `normalize_material` で揃え、空の素材は素材なしとする
+    pub fn set_material(&mut self, id: u64, material: &str) {
+        if let Some(r) = self.remnants.iter_mut().find(|r| r.id == id) {
+            r.material = normalize_material(material);
+        }
+    }
+
+    pub fn set_grain(&mut self, id: u64, grain: Grain) {
+        if let Some(r) = self.remnants.iter_mut().find(|r| r.id == id) {
+            r.grain = Some(grain);
+        }
+    }
+
+    /// 使用した端材を取り除く
+    pub fn remove(&mut self, id: u64) -> Option<StoredRemnant> {
+        let index = self.remnants.iter().position(|r| r.id == id)?;
+        Some(self.remnants.remove(index))
+    }
+
+    pub fn get(&self, id: u64) -> Option<&StoredRemnant> {
+        self.remnants.iter().find(|r| r.id == id)
+    }
+
+    /// 追加した順の端材一覧
+    pub fn remnants(&self) -> &[StoredRemnant] {
+        &self.remnants
+    }
+
+    pub fn len(&self) -> usize {
+        self.remnants.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remnants.is_empty()
+    }
+
+    /// 1行1項目のテキスト形式に変換する。
+    /// 端材の行は `remnant ID 幅 高さ 木目 [素材]` で、木目は `-`・`horizontal`・`vertical`
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{}\nmin {} {}\nnext {}\n", HEADER, self.min_width, self.min_height, self.next_id);
+        for r in &self.remnants {
+            let grain = match r.grain {
+                None => "-",
+                Some(Grain::Horizontal) => "horizontal",
+                Some(Grain::Vertical) => "vertical",
+            };
+            text.push_str(&format!("remnant {} {} {} {}", r.id, r.width, r.height, grain));
+            if let Some(material) = &r.material {
+                text.push(' ');
+                text.push_str(material);
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// `to_text` の形式を読み込む。空行と `#` で始まる行は無視する
+    pub fn from_text(text: &str) -> io::Result<Self> {
+        let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#'));
+        let version1 = match lines.next() {
+            Some(HEADER) => false,
+            Some(HEADER_V1) => true,
+            _ => return Err(invalid("missing header")),
+        };
+        let mut store = Self::new(0, 0);
+        for line in lines {
+            let fields = split_fields(line);
+            let number = |i: usize| fields.get(i).and_then(|f| f.parse::<u64>().ok()).ok_or_else(|| invalid(line));
+            match (fields[0], fields.len()) {
+                ("min", 3) => {
+                    store.min_width = to_u32(number(1)?, line)?;
+                    store.min_height = to_u32(number(2)?, line)?;
+                }
+                ("next", 2) => store.next_id = store.next_id.max(number(1)?),
+                ("remnant", 4) if version1 => store.push_remnant(number(1)?, number(2)?, number(3)?, None, None, line)?,
+                ("remnant", 5..=6) if !version1 => {
+                    let grain = match fields[4] {
+                        "-" => None,
+                        "horizontal" => Some(Grain::Horizontal),
+                        "vertical" => Some(Grain::Vertical),
+                        _ => return Err(invalid(line)),
+                    };
+                    let material = fields.get(5).and_then(|m| normalize_material(m));
+                    store.push_remnant(number(1)?, number(2)?, number(3)?, material, grain, line)?;
+                }
+                _ => return Err(invalid(line)),
+            }
+        }
+        Ok(store)
+    }
+
+    fn push_remnant(&mut self, id: u64, width: u64, height: u64, material: Option<String>, grain: Option<Grain>, line: &str) -> io::Result<()> {
+        if self.get(id).is_some() {
+            return Err(invalid(line));
+        }
+        self.remnants.push(StoredRemnant { id, width: to_u32(width, line)?, height: to_u32(height, line)?, material, grain });
+        self.next_id = self.next_id.max(id + 1);
+        Ok(())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_text(&fs::read_to_string(path)?)
+    }
+}
+
+/// 素材名の空白を1つの空白に揃える。空白だけの素材はNone。
+/// 保管庫のテキスト形式で読み戻したときに同じ素材になるよう、ビンや矩形の素材にも使う
+pub(crate) fn normalize_material(material: &str) -> Option<String> {
+    let normalized = material.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!normalized.is_empty()).then_some(normalized)
+}
+
+/// 行を空白で区切る。素材は空白を含んでもよいので、5項目より後ろはまとめて1項目とする
+fn split_fields(line: &str) -> Vec<&str> {
+    let mut fields = Vec::new();
+    let mut rest = line.trim();
+    while fields.len() < 5 && !rest.is_empty() {
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        fields.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+    if !rest.is_empty() {
+        fields.push(rest);
+    }
+    fields
+}
+
+fn to_u32(value: u64, line: &str) -> io::Result<u32> {
+    u32::try_from(value).map_err(|_| invalid(line))
+}
+
+fn invalid(line: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("invalid remnant store line: {}", line))
+}
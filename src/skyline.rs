@@ -1,4 +1,6 @@
-use crate::{Rectangle, Position};
+use std::collections::HashSet;
+
+use crate::{Rectangle, Position, Rotation};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SkylineNode {
@@ -7,22 +9,41 @@ pub struct SkylineNode {
     pub width: u32,
 }
 
-/// Skyline法によるアイテム配置を行う関数
-pub fn skyline_placement(rectangles: &[Rectangle], container_width: u32, container_height: u32, allow_rotate: bool) -> Vec<(Rectangle, Position, bool)> {
-    let mut placed_rectangles: Vec<(Rectangle, Position, bool)> = Vec::new();
-    let mut skyline: Vec<SkylineNode> = vec![SkylineNode { x: 0, y: 0, width: container_width }];
+/// スカイライン上の配置候補
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SkylineCandidate {
+    pub(crate) x: u32,
+    pub(crate) y: u32,
+    pub(crate) idx: usize,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) rotated: bool,
+}
 
-    for &rect in rectangles {
-        let mut candidates = vec![(rect.width, rect.height, false)];
-        if allow_rotate && rect.width != rect.height {
-            candidates.push((rect.height, rect.width, true));
+/// 1つのビンのスカイライン状態
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Skyline {
+    pub(crate) nodes: Vec<SkylineNode>,
+    container_height: Option<u32>,
+}
+
+impl Skyline {
+    pub(crate) fn new(container_width: u32, container_height: Option<u32>) -> Self {
+        Self {
+            nodes: vec![SkylineNode { x: 0, y: 0, width: container_width }],
+            container_height,
         }
-        let mut best_y = u32::MAX;
-        let mut best_x = 0;
-        let mut best_idx = None;
-        let mut best_rotated = false;
+    }
 
-        for &(w, h, rotated) in &candidates {
+    /// 配置可能な候補を貪欲法の優先順（低い順、同じ高さなら左から）に返す
+    pub(crate) fn candidates(&self, rect: Rectangle, allow_rotate: bool) -> Vec<SkylineCandidate> {
+        let skyline = &self.nodes;
+        let mut orientations = vec![(rect.width, rect.height, false)];
+        if allow_rotate && rect.width != rect.height {
+            orientations.push((rect.height, rect.width, true));
+        }
+        let mut result = Vec::new();
+        for &(w, h, rotated) in &orientations {
             // 複数ノードにまたがって幅を満たす場合も正しく判定
             for (i, node) in skyline.iter().enumerate() {
 
@@ -43,51 +64,87 @@ pub fn skyline_placement(rectangles: &[Rectangle], container_width: u32, contain
                     continue;
                 }
                 // 高さがはみ出す場合はスキップ
-                if y + h > container_height {
+                if self.container_height.is_some_and(|ch| y + h > ch) {
                     continue;
                 }
-                if y < best_y || (y == best_y && node.x < best_x) {
-                    best_y = y;
-                    best_x = node.x;
-                    best_idx = Some(i);
-                    best_rotated = rotated;
-
-                }
+                result.push(SkylineCandidate { x: node.x, y, idx: i, width: w, height: h, rotated });
             }
         }
-        if let Some(idx) = best_idx {
-            let pos = Position { x: best_x, y: best_y };
-            placed_rectangles.push((rect, pos, best_rotated));
-            let (w, h) = if best_rotated { (rect.height, rect.width) } else { (rect.width, rect.height) };
-            // スカイラインを更新
-            // 1. 配置した矩形の上に新ノードを追加
-            let new_node = SkylineNode { x: best_x, y: best_y + h, width: w };
-            skyline.insert(idx, new_node);
-            // 2. 配置範囲にかかる既存ノードを分割・削除
-            let i = idx + 1;
-            while i < skyline.len() {
-                if skyline[i].x < best_x + w {
-                    let overlap = (best_x + w).saturating_sub(skyline[i].x);
-                    if skyline[i].width > overlap {
-                        skyline[i].x += overlap;
-                        skyline[i].width -= overlap;
-                        break;
-                    } else {
-                        skyline.remove(i);
-                    }
-                } else {
+        // 安定ソートなので同じ位置なら回転しない方が先になる
+        result.sort_by_key(|c| (c.y, c.x));
+        result
+    }
+
+    pub(crate) fn place(&mut self, candidate: &SkylineCandidate) {
+        let skyline = &mut self.nodes;
+        let (best_x, best_y, idx) = (candidate.x, candidate.y, candidate.idx);
+        let (w, h) = (candidate.width, candidate.height);
+        // スカイラインを更新
+        // 1. 配置した矩形の上に新ノードを追加
+        let new_node = SkylineNode { x: best_x, y: best_y + h, width: w };
+        skyline.insert(idx, new_node);
+        // 2. 配置範囲にかかる既存ノードを分割・削除
+        let i = idx + 1;
+        while i < skyline.len() {
+            if skyline[i].x < best_x + w {
+                let overlap = (best_x + w).saturating_sub(skyline[i].x);
+                if skyline[i].width > overlap {
+                    skyline[i].x += overlap;
+                    skyline[i].width -= overlap;
                     break;
-                }
-            }
-            // 3. 隣接ノードの高さが同じなら結合
-            let mut i = 0;
-            while i + 1 < skyline.len() {
-                if skyline[i].y == skyline[i + 1].y {
-                    skyline[i].width += skyline[i + 1].width;
-                    skyline.remove(i + 1);
                 } else {
-                    i += 1;
+                    skyline.remove(i);
                 }
+            } else {
+                break;
+            }
+        }
+        // 3. 隣接ノードの高さが同じなら結合
+        let mut i = 0;
+        while i + 1 < skyline.len() {
+            if skyline[i].y == skyline[i + 1].y {
+                skyline[i].width += skyline[i + 1].width;
+                skyline.remove(i + 1);
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    /// スカイラインより下の面積
+    pub(crate) fn area_below(&self) -> u64 {
+        self.nodes.iter().map(|n| n.width as u64 * n.y as u64).sum()
+    }
+
+    pub(crate) fn max_height(&self) -> u32 {
+        self.nodes.iter().map(|n| n.y).max().unwrap_or(0)
+    }
+}
+
+/// Skyline法によるアイテム配置を行う関数
+pub fn skyline_placement(rectangles: &[Rectangle], container_width: u32, container_height: u32, allow_rotate: bool) -> Vec<(Rectangle, Position, bool)> {
+    skyline_placement_in(rectangles, container_width, Some(container_height), Rotation::All(allow_rotate))
+}
+
+/// 高さを省略した場合は上方向に制限のないストリップとして配置する
+pub(crate) fn skyline_placement_in(rectangles: &[Rectangle], container_width: u32, container_height: Option<u32>, rotation: Rotation) -> Vec<(Rectangle, Position, bool)> {
+    let mut placed_rectangles: Vec<(Rectangle, Position, bool)> = Vec::new();
+    let mut skyline = Skyline::new(container_width, container_height);
+
+    // スカイラインは上がるだけなので、一度入らなかったサイズは以降も入らない
+    let mut failed: HashSet<(u32, u32, bool)> = HashSet::new();
+    for (index, &rect) in rectangles.iter().enumerate() {
+        let key = (rect.width, rect.height, rotation.allows(index));
+        if failed.contains(&key) {
+            continue;
+        }
+        match skyline.candidates(rect, key.2).first().copied() {
+            Some(best) => {
+                placed_rectangles.push((rect, Position { x: best.x, y: best.y }, best.rotated));
+                skyline.place(&best);
+            }
+            None => {
+                failed.insert(key);
             }
         }
     }
@@ -0,0 +1,127 @@
+use std::cmp::Reverse;
+
+use crate::{Rectangle, Position, Rotation};
+
+/// 切断段数の制限
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StageLimit {
+    /// 1: 横切りだけで部品を切り出す（ストリップ1本に部品1つ）。
+    /// 2: 横切りでストリップ、縦切りで部品。3: さらに部品を縦に積んだ列を横切りで分ける。
+    /// 0はビンと同じサイズの部品だけ、3を超える値は3として扱う
+    pub stages: u32,
+    /// trueならトリミング（高さや幅の余りを切り落とす追加の切断）を許す
+    pub trimming: bool,
+}
+
+impl StageLimit {
+    pub fn one_stage(trimming: bool) -> Self {
+        Self { stages: 1, trimming }
+    }
+
+    pub fn two_stage(trimming: bool) -> Self {
+        Self { stages: 2, trimming }
+    }
+
+    pub fn three_stage(trimming: bool) -> Self {
+        Self { stages: 3, trimming }
+    }
+}
+
+/// ビン全幅のストリップ（1段目の切断で得られる）
+struct Strip {
+    y: u32,
+    height: u32,
+    /// 次の部品（列）を置くx座標
+    next_x: u32,
+    columns: Vec<Column>,
+}
+
+/// ストリップ内で部品を縦に積んだ列（3段の場合）
+struct Column {
+    x: u32,
+    width: u32,
+    /// 次の部品を置くストリップ内のy方向のオフセット
+    next_y: u32,
+}
+
+/// 段数を制限したGuillotine法。
+/// ストリップ→部品（→列内の部品）という段階的なパターンだけを作る
+pub fn staged_guillotine_placement(rectangles: &[Rectangle], container_width: u32, container_height: u32, allow_rotate: bool, limit: &StageLimit) -> Vec<(Rectangle, Position, bool)> {
+    staged_placement_in(rectangles, container_width, container_height, Rotation::All(allow_rotate), limit, 0)
+}
+
+pub(crate) fn staged_placement_in(rectangles: &[Rectangle], container_width: u32, container_height: u32, rotation: Rotation, limit: &StageLimit, kerf: u32) -> Vec<(Rectangle, Position, bool)> {
+    let three_stage = limit.stages >= 3;
+    // 1段ではストリップの幅方向を切らないので、ストリップに部品は1つだけ
+    let shared_strips = limit.stages >= 2;
+    let orientations = |index: usize| {
+        let rect = rectangles[index];
+        let mut result = vec![(rect.width, rect.height, false)];
+        if rotation.allows(index) && rect.width != rect.height {
+            result.push((rect.height, rect.width, true));
+        }
+        result.retain(|&(w, h, _)| w <= container_width && h <= container_height);
+        result
+    };
+    // 新しいストリップを開くときの高さ（低い向き）が高い順に配置する
+    let strip_height = |index: usize| orientations(index).iter().map(|&(_, h, _)| h).min();
+    let mut order: Vec<usize> = (0..rectangles.len()).collect();
+    order.sort_by_key(|&i| (Reverse(strip_height(i)), Reverse(rectangles[i].width.max(rectangles[i].height))));
+
+    let mut strips: Vec<Strip> = Vec::new();
+    let mut next_strip_y = 0;
+    let mut positions: Vec<Option<(Position, bool)>> = vec![None; rectangles.len()];
+    for i in order {
+        let candidates = orientations(i);
+        let mut placed = None;
+        'strips: for strip in strips.iter_mut().filter(|_| shared_strips) {
+            if three_stage {
+                for column in strip.columns.iter_mut() {
+                    for &(w, h, rotated) in &candidates {
+                        let width_ok = if limit.trimming { w <= column.width } else { w == column.width };
+                        if width_ok && column.next_y + h <= strip.height {
+                            placed = Some((Position { x: column.x, y: strip.y + column.next_y }, rotated));
+                            column.next_y += h + kerf;
+                            break 'strips;
+                        }
+                    }
+                }
+            }
+            for &(w, h, rotated) in &candidates {
+                // 3段ならストリップの高さ未満の列は3段目で切り分け、2段ならトリミングが必要
+                let height_ok = if three_stage || limit.trimming { h <= strip.height } else { h == strip.height };
+                if height_ok && strip.next_x + w <= container_width {
+                    placed = Some((Position { x: strip.next_x, y: strip.y }, rotated));
+                    if three_stage {
+                        strip.columns.push(Column { x: strip.next_x, width: w, next_y: h + kerf });
+                    }
+                    strip.next_x += w + kerf;
+                    break 'strips;
+                }
+            }
+        }
+        if placed.is_none() {
+            // 最も低くなる向きで新しいストリップを開く
+            let best = candidates
+                .iter()
+                .filter(|&&(_, h, _)| next_strip_y + h <= container_height)
+                // 幅方向の余りは縦切りかトリミングが必要
+                .filter(|&&(w, _, _)| shared_strips || (limit.trimming && limit.stages == 1) || w == container_width)
+                // 切断しない場合はビンそのものが部品になる
+                .filter(|&&(_, h, _)| limit.stages > 0 || h == container_height)
+                .min_by_key(|&&(_, h, _)| h);
+            if let Some(&(w, h, rotated)) = best {
+                placed = Some((Position { x: 0, y: next_strip_y }, rotated));
+                let columns = if three_stage { vec![Column { x: 0, width: w, next_y: h + kerf }] } else { Vec::new() };
+                strips.push(Strip { y: next_strip_y, height: h, next_x: w + kerf, columns });
+                next_strip_y += h + kerf;
+            }
+        }
+        positions[i] = placed;
+    }
+    rectangles
+        .iter()
+        .zip(positions)
+        .filter_map(|(&rect, placed)| placed.map(|(pos, rotated)| (rect, pos, rotated)))
+        .collect()
+}
@@ -0,0 +1,21 @@
+use crate::{Rectangle, Position, PackAlgorithm, Rotation};
+
+/// ストリップパッキングの結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StripLayout {
+    pub placements: Vec<(Rectangle, Position, bool)>,
+    /// 使用した高さ
+    pub height: u32,
+}
+
+/// 幅を固定し高さを無制限として配置し、使用した高さを返す関数。
+/// 幅に収まらない矩形は配置されない
+pub fn strip_pack(rectangles: &[Rectangle], strip_width: u32, algorithm: PackAlgorithm, allow_rotate: bool) -> StripLayout {
+    let placements = algorithm.place_in(rectangles, strip_width, None, Rotation::All(allow_rotate));
+    let height = placements
+        .iter()
+        .map(|&(rect, pos, rotated)| pos.y + if rotated { rect.width } else { rect.height })
+        .max()
+        .unwrap_or(0);
+    StripLayout { placements, height }
+}
@@ -0,0 +1,61 @@
+use rustpack::*;
+
+fn placed_area(placed: &[(Rectangle, Position, bool)]) -> u32 {
+    placed.iter().map(|(r, _, _)| r.width * r.height).sum()
+}
+
+fn pinwheel() -> Vec<Rectangle> {
+    let mut rects = vec![Rectangle { width: 2, height: 2 }];
+    rects.extend(vec![Rectangle { width: 6, height: 4 }; 4]);
+    rects
+}
+
+#[test]
+fn test_annealing_finds_perfect_pinwheel() {
+    let rects = pinwheel();
+    let greedy = skyline_placement(&rects, 10, 10, true);
+    let config = AnnealingConfig { seed: 1, max_iterations: Some(2000), ..Default::default() };
+    let improved = anneal_placement(&rects, 10, 10, skyline_placement, &config);
+
+    assert!(placed_area(&greedy) < 100);
+    assert_eq!(placed_area(&improved), 100);
+    for &(r, p, rot) in &improved {
+        let (w, h) = if rot { (r.height, r.width) } else { (r.width, r.height) };
+        assert!(p.x + w <= 10 && p.y + h <= 10);
+    }
+}
+
+#[test]
+fn test_annealing_never_worse_than_greedy() {
+    let rects: Vec<Rectangle> = (0..25).map(|i| Rectangle {
+        width: (i * 7 % 11) + 2,
+        height: (i * 5 % 9) + 2,
+    }).collect();
+    let decoders: [PlacementFn; 4] = [bottom_left_placement, maxrects_placement, skyline_placement, guillotine_placement];
+    for decoder in decoders {
+        for seed in 0..3 {
+            let greedy = decoder(&rects, 30, 30, true);
+            let config = AnnealingConfig { seed, max_iterations: Some(50), ..Default::default() };
+            let improved = anneal_placement(&rects, 30, 30, decoder, &config);
+            assert!(placed_area(&improved) >= placed_area(&greedy));
+        }
+    }
+}
+
+#[test]
+fn test_annealing_is_reproducible_and_time_bounded() {
+    let rects = pinwheel();
+    let config = AnnealingConfig { seed: 42, max_iterations: Some(300), ..Default::default() };
+    let a = anneal_placement(&rects, 10, 10, bottom_left_placement, &config);
+    let b = anneal_placement(&rects, 10, 10, bottom_left_placement, &config);
+    assert_eq!(a, b);
+
+    let config = AnnealingConfig {
+        max_iterations: None,
+        time_limit: Some(std::time::Duration::from_millis(20)),
+        ..Default::default()
+    };
+    let start = std::time::Instant::now();
+    anneal_placement(&rects, 10, 10, skyline_placement, &config);
+    assert!(start.elapsed() < std::time::Duration::from_secs(2));
+}
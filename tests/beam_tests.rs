@@ -0,0 +1,48 @@
+use rustpack::*;
+
+fn placed_area(placed: &[(Rectangle, Position, bool)]) -> u32 {
+    placed.iter().map(|(r, _, _)| r.width * r.height).sum()
+}
+
+fn instance(seed: u32) -> Vec<Rectangle> {
+    (0..25).map(|i| Rectangle {
+        width: (i * 7 + seed) % 11 + 2,
+        height: (i * 5 + seed * 3) % 9 + 2,
+    }).collect()
+}
+
+#[test]
+fn test_beam_width_one_matches_skyline() {
+    for seed in 0..10 {
+        let rects = instance(seed);
+        for rotate in [false, true] {
+            assert_eq!(
+                beam_search_placement(&rects, 30, 30, rotate, 1),
+                skyline_placement(&rects, 30, 30, rotate)
+            );
+        }
+    }
+}
+
+#[test]
+fn test_wider_beam_never_worse() {
+    for seed in 0..10 {
+        let rects = instance(seed);
+        let greedy = skyline_placement(&rects, 30, 30, true);
+        let beam = beam_search_placement(&rects, 30, 30, true, 6);
+        assert!(placed_area(&beam) >= placed_area(&greedy));
+    }
+}
+
+#[test]
+fn test_beam_improves_pinwheel() {
+    let mut rects = vec![Rectangle { width: 6, height: 4 }; 4];
+    rects.push(Rectangle { width: 2, height: 2 });
+    let greedy = skyline_placement(&rects, 10, 10, true);
+    let beam = beam_search_placement(&rects, 10, 10, true, 4);
+    assert!(placed_area(&beam) > placed_area(&greedy));
+    for &(r, p, rot) in &beam {
+        let (w, h) = if rot { (r.height, r.width) } else { (r.width, r.height) };
+        assert!(p.x + w <= 10 && p.y + h <= 10);
+    }
+}
@@ -0,0 +1,67 @@
+use rustpack::*;
+
+#[test]
+fn test_pin_to_bin() {
+    let mut packer = Packer::new();
+    packer.add_rect(Rectangle { width: 5, height: 5 });
+    let pinned = packer.add_rect(Rectangle { width: 5, height: 5 });
+    packer.add_bin(20, 20);
+    let last = packer.add_bin(20, 20);
+    packer.pin_to_bin(pinned, last);
+
+    packer.pack();
+
+    let rects = packer.rect_list();
+    assert_eq!(rects.len(), 2);
+    let r = rects.iter().find(|r| r.rect_id == pinned).unwrap();
+    assert_eq!(r.bin_id, last);
+    assert_eq!((r.x, r.y), (0, 0));
+}
+
+#[test]
+fn test_allowed_bins_by_tag() {
+    let mut packer = Packer::new();
+    let glow = packer.add_rect(Rectangle { width: 8, height: 8 });
+    packer.add_rect(Rectangle { width: 8, height: 8 });
+    packer.set_allowed_bins(glow, BinFilter::Tags(vec!["glow".to_string()]));
+
+    packer.add_bin(10, 10);
+    packer.add_bin(10, 10);
+    let glow_page = packer.add_bin(10, 10);
+    packer.add_bin_tag(glow_page, "glow");
+
+    packer.pack();
+
+    let rects = packer.rect_list();
+    assert_eq!(rects.len(), 2);
+    assert_eq!(rects.iter().find(|r| r.rect_id == glow).unwrap().bin_id, glow_page);
+    assert_eq!(rects.iter().find(|r| r.rect_id != glow).unwrap().bin_id, 0);
+}
+
+#[test]
+fn test_no_allowed_bin_leaves_rect_unplaced() {
+    let mut packer = Packer::new();
+    let r = packer.add_rect(Rectangle { width: 2, height: 2 });
+    packer.set_allowed_bins(r, BinFilter::Ids(vec![]));
+    packer.add_bin(10, 10);
+
+    packer.pack();
+
+    assert!(packer.rect_list().is_empty());
+}
+
+#[test]
+fn test_group_follows_member_restrictions() {
+    let mut packer = Packer::new();
+    let a = packer.add_rect_to_group(Rectangle { width: 3, height: 3 }, 1);
+    packer.add_rect_to_group(Rectangle { width: 3, height: 3 }, 1);
+    packer.add_bin(10, 10);
+    let second = packer.add_bin(10, 10);
+    packer.pin_to_bin(a, second);
+
+    packer.pack();
+
+    let rects = packer.rect_list();
+    assert_eq!(rects.len(), 2);
+    assert!(rects.iter().all(|r| r.bin_id == second));
+}
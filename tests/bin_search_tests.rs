@@ -0,0 +1,133 @@
+use rustpack::*;
+
+#[test]
+fn test_power_of_two_search() {
+    let rects = vec![Rectangle { width: 8, height: 8 }; 4];
+    let constraints = BinSizeConstraints { power_of_two: true, ..Default::default() };
+    let result = search_bin_size(&rects, PackAlgorithm::MaxRects, false, &constraints).unwrap();
+    assert_eq!(result.width * result.height, 256);
+    assert!(result.width.is_power_of_two() && result.height.is_power_of_two());
+    assert_eq!(result.placements.len(), 4);
+}
+
+#[test]
+fn test_square_search() {
+    let rects = vec![Rectangle { width: 10, height: 10 }; 4];
+    let constraints = BinSizeConstraints { square: true, ..Default::default() };
+    let result = search_bin_size(&rects, PackAlgorithm::Skyline, false, &constraints).unwrap();
+    assert_eq!((result.width, result.height), (20, 20));
+}
+
+#[test]
+fn test_minimum_area_search() {
+    let rects = vec![
+        Rectangle { width: 6, height: 4 },
+        Rectangle { width: 4, height: 4 },
+        Rectangle { width: 10, height: 3 },
+    ];
+    let result = search_bin_size(&rects, PackAlgorithm::Skyline, false, &BinSizeConstraints::default()).unwrap();
+    assert_eq!(result.width * result.height, 70);
+    assert_eq!(result.placements.len(), 3);
+}
+
+#[test]
+fn test_aspect_ratio_and_max_side() {
+    let rects = vec![Rectangle { width: 30, height: 10 }];
+    let constraints = BinSizeConstraints { max_aspect_ratio: Some(2.0), ..Default::default() };
+    let result = search_bin_size(&rects, PackAlgorithm::MaxRects, false, &constraints).unwrap();
+    assert_eq!((result.width, result.height), (30, 15));
+
+    let constraints = BinSizeConstraints { max_side: Some(20), ..Default::default() };
+    assert!(search_bin_size(&rects, PackAlgorithm::MaxRects, false, &constraints).is_none());
+    // 回転を許せば縦長のビンでもよい
+    let constraints = BinSizeConstraints { max_side: Some(30), ..Default::default() };
+    assert!(search_bin_size(&rects, PackAlgorithm::MaxRects, true, &constraints).is_some());
+}
+
+#[test]
+fn test_packer_auto_size() {
+    let mut packer = Packer::new();
+    packer.set_pack_algo(PackAlgorithm::Skyline);
+    for _ in 0..3 {
+        packer.add_rect(Rectangle { width: 16, height: 16 });
+    }
+    packer.add_bin(1, 1);
+
+    let constraints = BinSizeConstraints { power_of_two: true, square: true, ..Default::default() };
+    let bin = packer.pack_auto_size(&constraints).unwrap();
+    assert_eq!(bin, Bin { width: 32, height: 32 });
+    assert_eq!(packer.rect_list().len(), 3);
+    assert!(packer.rect_list().iter().all(|r| r.bin_id == 0));
+}
+
+#[test]
+fn test_packer_auto_size_with_kerf() {
+    let mut packer = Packer::new();
+    packer.set_kerf(4);
+    for _ in 0..4 {
+        packer.add_rect(Rectangle { width: 8, height: 8 });
+    }
+    let bin = packer.pack_auto_size(&BinSizeConstraints::default()).unwrap();
+    let rects = packer.rect_list();
+    assert_eq!(rects.len(), 4);
+    // 4つの間には少なくとも3回分のkerfが入る
+    assert!(bin.width as u64 * bin.height as u64 >= 8 * 44);
+    for (i, a) in rects.iter().enumerate() {
+        for b in &rects[i + 1..] {
+            let gap_x = b.x.saturating_sub(a.x + a.width).max(a.x.saturating_sub(b.x + b.width));
+            let gap_y = b.y.saturating_sub(a.y + a.height).max(a.y.saturating_sub(b.y + b.height));
+            assert!(gap_x >= 4 || gap_y >= 4);
+        }
+    }
+}
+
+#[test]
+fn test_packer_auto_size_respects_bin_filters() {
+    let mut packer = Packer::new();
+    let bin = packer.add_bin(1, 1);
+    packer.add_bin_tag(bin, "thin");
+    let a = packer.add_rect(Rectangle { width: 8, height: 8 });
+    packer.set_allowed_bins(a, BinFilter::Tags(vec!["thin".to_string()]));
+    let b = packer.add_rect(Rectangle { width: 8, height: 8 });
+    packer.set_allowed_bins(b, BinFilter::Tags(vec!["thick".to_string()]));
+    // bはどのサイズでも置けないので、一部だけ配置したサイズは返さない
+    assert_eq!(packer.pack_auto_size(&BinSizeConstraints::default()), None);
+
+    packer.set_allowed_bins(b, BinFilter::Ids(vec![bin]));
+    // 置き換えたビンは元のビンのタグを引き継ぐ
+    assert_eq!(packer.pack_auto_size(&BinSizeConstraints::default()), Some(Bin { width: 8, height: 16 }));
+    assert_eq!(packer.rect_list().len(), 2);
+}
+
+#[test]
+fn test_search_sprite_sheet_is_fast_and_compact() {
+    // 16〜256pxのスプライト150個（疑似乱数で決定的に生成）
+    let mut seed = 12345u64;
+    let mut next = || {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        16 + ((seed >> 33) % 241) as u32
+    };
+    let rects: Vec<Rectangle> = (0..150).map(|_| Rectangle { width: next(), height: next() }).collect();
+    let area: u64 = rects.iter().map(|r| r.width as u64 * r.height as u64).sum();
+
+    for algo in [PackAlgorithm::Skyline, PackAlgorithm::MaxRects] {
+        let start = std::time::Instant::now();
+        let result = search_bin_size(&rects, algo, true, &BinSizeConstraints::default()).unwrap();
+        assert!(start.elapsed() < std::time::Duration::from_secs(5), "{:?} took {:?}", algo, start.elapsed());
+        assert_eq!(result.placements.len(), 150);
+        let (long, short) = (result.width.max(result.height), result.width.min(result.height));
+        assert!(long as f64 / short as f64 <= 4.0, "{}x{}", result.width, result.height);
+        assert!((result.width as u64 * result.height as u64) < area * 2, "{}x{}", result.width, result.height);
+    }
+
+    let mut packer = Packer::new();
+    packer.set_pack_algo(PackAlgorithm::Skyline);
+    for r in &rects {
+        packer.add_rect(*r);
+    }
+    let start = std::time::Instant::now();
+    let bin = packer.pack_auto_size(&BinSizeConstraints::default()).unwrap();
+    assert!(start.elapsed() < std::time::Duration::from_secs(5), "pack_auto_size took {:?}", start.elapsed());
+    assert!(bin.width.max(bin.height) as f64 / bin.width.min(bin.height) as f64 <= 4.0);
+    assert_eq!(packer.rect_list().len(), 150);
+}
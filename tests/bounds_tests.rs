@@ -0,0 +1,70 @@
+use rustpack::*;
+
+#[test]
+fn test_large_items_bound() {
+    // 面積だけなら2ビンだが、6x6は1ビンに1つしか入らない
+    let rects = vec![Rectangle { width: 6, height: 6 }; 4];
+    let bounds = lower_bounds(&rects, 10, 10, true);
+    assert_eq!(bounds.continuous, 2);
+    assert_eq!(bounds.l1, 4);
+    assert_eq!(bounds.l2, 4);
+    assert_eq!(bounds.best(), 4);
+}
+
+#[test]
+fn test_rotation_weakens_bound() {
+    let rects = vec![Rectangle { width: 3, height: 8 }; 4];
+    let fixed = lower_bounds(&rects, 10, 10, false);
+    let rotating = lower_bounds(&rects, 10, 10, true);
+    assert_eq!(fixed.l1, 2);
+    assert!(rotating.best() <= fixed.best());
+}
+
+#[test]
+fn test_bounds_never_exceed_optimum() {
+    for seed in 0..20u32 {
+        let rects: Vec<Rectangle> = (0..8).map(|i| Rectangle {
+            width: (i * 7 + seed * 3) % 8 + 2,
+            height: (i * 5 + seed) % 8 + 2,
+        }).collect();
+        for rotate in [false, true] {
+            let result = exact_min_bins(&rects, 10, 10, rotate, &ExactLimits { max_nodes: Some(200_000), time_limit: None });
+            if result.optimal {
+                assert!(lower_bounds(&rects, 10, 10, rotate).best() <= result.bins);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_packer_summary() {
+    let mut packer = Packer::new();
+    for _ in 0..4 {
+        packer.add_rect(Rectangle { width: 6, height: 6 });
+        packer.add_bin(10, 10);
+    }
+    packer.pack();
+    let summary = packer.summary();
+    assert_eq!(summary.bins_used, 4);
+    assert_eq!(summary.lower_bound, 4);
+    assert_eq!(summary.unplaced, 0);
+    assert!(summary.is_optimal());
+    assert_eq!(summary.gap(), 0);
+}
+
+#[test]
+fn test_summary_includes_unused_stock_sizes() {
+    let mut packer = Packer::new();
+    for _ in 0..4 {
+        packer.add_rect(Rectangle { width: 5, height: 5 });
+    }
+    packer.add_stock(5, 5, 10, 1.0);
+    packer.add_stock(10, 10, 10, 100.0);
+    packer.pack();
+    let summary = packer.summary();
+    // コストでは小さい在庫4枚が選ばれるが、大きい在庫なら1枚で済む
+    assert_eq!(summary.bins_used, 4);
+    assert_eq!(summary.lower_bound, 1);
+    assert!(!summary.is_optimal());
+    assert_eq!(summary.gap(), 3);
+}
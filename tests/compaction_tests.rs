@@ -0,0 +1,87 @@
+use rustpack::*;
+
+fn rect(x: u32, y: u32, width: u32, height: u32, bin_id: usize) -> PackedRect {
+    PackedRect { x, y, width, height, rotated: false, bin_id, rect_id: 0, in_remnant: false }
+}
+
+fn assert_no_overlap(rects: &[PackedRect]) {
+    for (i, a) in rects.iter().enumerate() {
+        for b in &rects[i + 1..] {
+            if a.bin_id == b.bin_id {
+                assert!(a.x + a.width <= b.x || b.x + b.width <= a.x || a.y + a.height <= b.y || b.y + b.height <= a.y);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_compact_down() {
+    let mut rects = vec![rect(0, 5, 2, 2, 0), rect(1, 10, 2, 2, 0), rect(5, 8, 2, 2, 0)];
+    compact(&mut rects, Gravity::Down);
+    assert_eq!((rects[0].x, rects[0].y), (0, 0));
+    assert_eq!((rects[1].x, rects[1].y), (1, 2));
+    assert_eq!((rects[2].x, rects[2].y), (5, 0));
+}
+
+#[test]
+fn test_compact_down_left_per_bin() {
+    let mut rects = vec![rect(4, 4, 2, 2, 0), rect(4, 4, 2, 2, 1), rect(9, 0, 1, 1, 0)];
+    compact(&mut rects, Gravity::DownLeft);
+    // ビンが違えば互いに影響しない
+    assert_eq!((rects[0].x, rects[0].y), (0, 0));
+    assert_eq!((rects[1].x, rects[1].y), (0, 0));
+    assert_eq!((rects[2].x, rects[2].y), (2, 0));
+}
+
+#[test]
+fn test_packer_compact_never_overlaps() {
+    let mut packer = Packer::new();
+    packer.set_pack_algo(PackAlgorithm::Guillotine);
+    for i in 0..30 {
+        packer.add_rect(Rectangle { width: (i % 6) + 2, height: (i % 4) + 3 });
+    }
+    packer.add_bin(30, 30);
+    packer.add_bin(30, 30);
+    packer.pack();
+    let before = packer.rect_list().to_vec();
+    let height_before = before.iter().map(|r| r.y + r.height).max().unwrap();
+
+    packer.compact(Gravity::DownLeft);
+
+    let after = packer.rect_list();
+    assert_eq!(after.len(), before.len());
+    assert_no_overlap(after);
+    assert!(after.iter().map(|r| r.y + r.height).max().unwrap() <= height_before);
+    for (a, b) in after.iter().zip(&before) {
+        assert!(a.x <= b.x && a.y <= b.y);
+    }
+}
+
+#[test]
+fn test_compact_keeps_kerf_gap() {
+    let mut rects = vec![rect(0, 0, 10, 4, 0), rect(13, 0, 4, 4, 0), rect(0, 9, 4, 4, 0), rect(11, 9, 2, 2, 0)];
+    compact_with_kerf(&mut rects, Gravity::DownLeft, 3);
+    assert_eq!((rects[1].x, rects[1].y), (13, 0));
+    assert_eq!((rects[2].x, rects[2].y), (0, 7));
+    for (i, a) in rects.iter().enumerate() {
+        for b in &rects[i + 1..] {
+            let gap_x = b.x.saturating_sub(a.x + a.width).max(a.x.saturating_sub(b.x + b.width));
+            let gap_y = b.y.saturating_sub(a.y + a.height).max(a.y.saturating_sub(b.y + b.height));
+            assert!(gap_x >= 3 || gap_y >= 3);
+        }
+    }
+}
+
+#[test]
+fn test_packer_compact_uses_kerf() {
+    let mut packer = Packer::new();
+    packer.set_pack_algo(PackAlgorithm::Guillotine);
+    packer.set_kerf(3);
+    packer.add_rect(Rectangle { width: 10, height: 10 });
+    packer.add_rect(Rectangle { width: 5, height: 10 });
+    packer.add_bin(20, 10);
+    packer.pack();
+    assert_eq!(packer.rect_list()[1].x, 13);
+    packer.compact(Gravity::Left);
+    assert_eq!(packer.rect_list()[1].x, 13);
+}
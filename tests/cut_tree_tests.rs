@@ -0,0 +1,75 @@
+use rustpack::*;
+
+fn leaves(node: &CutNode, out: &mut Vec<usize>) {
+    if let Some(i) = node.item {
+        out.push(i);
+    }
+    for child in &node.children {
+        leaves(child, out);
+    }
+}
+
+#[test]
+fn test_three_stage_cut_sequence() {
+    let placements = vec![
+        (Rectangle { width: 4, height: 4 }, Position { x: 0, y: 0 }, false),
+        (Rectangle { width: 4, height: 2 }, Position { x: 4, y: 0 }, false),
+        (Rectangle { width: 4, height: 2 }, Position { x: 4, y: 2 }, false),
+    ];
+    let tree = cut_tree(&placements, 8, 4, 0).unwrap();
+    let cuts: Vec<(CutOrientation, u32, u32)> = tree.cut_sequence().iter().map(|c| (c.orientation, c.position, c.depth)).collect();
+    assert_eq!(cuts, vec![(CutOrientation::Vertical, 4, 1), (CutOrientation::Horizontal, 2, 2)]);
+    assert_eq!(tree.cut_sequence()[1].piece, GuillotineRect { position: Position { x: 4, y: 0 }, width: 4, height: 4 });
+}
+
+#[test]
+fn test_guillotine_layouts_are_cuttable() {
+    let rects: Vec<Rectangle> = (0..25).map(|i| Rectangle { width: (i * 7) % 9 + 2, height: (i * 5) % 6 + 2 }).collect();
+    for kerf in [0, 2] {
+        let placed = guillotine_placement_with_kerf(&rects, 40, 40, true, kerf);
+        let tree = cut_tree(&placed, 40, 40, kerf).expect("guillotine layout");
+        let mut items = Vec::new();
+        leaves(&tree, &mut items);
+        items.sort();
+        assert_eq!(items, (0..placed.len()).collect::<Vec<_>>());
+        for cut in tree.cut_sequence() {
+            let (lo, hi) = match cut.orientation {
+                CutOrientation::Vertical => (cut.piece.position.x, cut.piece.position.x + cut.piece.width),
+                CutOrientation::Horizontal => (cut.piece.position.y, cut.piece.position.y + cut.piece.height),
+            };
+            assert!(lo < cut.position && cut.position < hi);
+        }
+    }
+}
+
+#[test]
+fn test_pinwheel_is_not_cuttable() {
+    let placements = vec![
+        (Rectangle { width: 6, height: 4 }, Position { x: 0, y: 0 }, false),
+        (Rectangle { width: 4, height: 6 }, Position { x: 6, y: 0 }, false),
+        (Rectangle { width: 6, height: 4 }, Position { x: 4, y: 6 }, false),
+        (Rectangle { width: 4, height: 6 }, Position { x: 0, y: 4 }, false),
+        (Rectangle { width: 2, height: 2 }, Position { x: 4, y: 4 }, false),
+    ];
+    assert!(cut_tree(&placements, 10, 10, 0).is_none());
+}
+
+#[test]
+fn test_packer_cut_tree_uses_rect_ids() {
+    let mut packer = Packer::new();
+    packer.set_pack_algo(PackAlgorithm::Guillotine);
+    packer.add_bin(10, 10);
+    packer.add_bin(10, 10);
+    let big = packer.add_rect(Rectangle { width: 10, height: 10 });
+    let a = packer.add_rect(Rectangle { width: 5, height: 10 });
+    let b = packer.add_rect(Rectangle { width: 5, height: 10 });
+    packer.pack();
+    let tree = packer.cut_tree(0).unwrap();
+    assert_eq!(tree.item, Some(big));
+    let tree = packer.cut_tree(1).unwrap();
+    let mut items = Vec::new();
+    leaves(&tree, &mut items);
+    items.sort();
+    assert_eq!(items, vec![a, b]);
+    assert!(packer.cut_tree(5).is_none());
+}
@@ -0,0 +1,82 @@
+use rustpack::*;
+
+fn mixed() -> Vec<(Rectangle, usize)> {
+    vec![(Rectangle { width: 7, height: 3 }, 10), (Rectangle { width: 3, height: 3 }, 23)]
+}
+
+#[test]
+fn test_identical_sheets_form_one_pattern() {
+    let plan = cutting_stock(&[(Rectangle { width: 5, height: 5 }, 20)], 10, 10, PackAlgorithm::MaxRects, false, 0.0);
+    assert_eq!(plan.patterns.len(), 1);
+    assert_eq!(plan.patterns[0].count, 5);
+    assert_eq!(plan.patterns[0].placements.len(), 4);
+    assert_eq!(plan.sheets, 5);
+    assert!(plan.unplaced.is_empty());
+}
+
+#[test]
+fn test_plan_meets_demand_exactly() {
+    let demands = mixed();
+    for tolerance in [0.0, 0.1, 0.3] {
+        let plan = cutting_stock(&demands, 10, 10, PackAlgorithm::MaxRects, true, tolerance);
+        assert!(plan.unplaced.is_empty());
+        for &(rect, quantity) in &demands {
+            let produced: usize = plan.patterns.iter()
+                .map(|p| p.count * p.placements.iter().filter(|(r, _, _)| *r == rect).count())
+                .sum();
+            assert_eq!(produced, quantity);
+        }
+        assert_eq!(plan.sheets, plan.patterns.iter().map(|p| p.count).sum::<usize>());
+    }
+}
+
+#[test]
+fn test_tolerance_reduces_patterns() {
+    let exact = cutting_stock(&mixed(), 10, 10, PackAlgorithm::MaxRects, true, 0.0);
+    let relaxed = cutting_stock(&mixed(), 10, 10, PackAlgorithm::MaxRects, true, 0.05);
+    assert!(relaxed.patterns.len() < exact.patterns.len());
+    assert!(relaxed.sheets >= exact.sheets);
+}
+
+#[test]
+fn test_oversized_items_unplaced() {
+    let demands = vec![(Rectangle { width: 12, height: 2 }, 3), (Rectangle { width: 5, height: 5 }, 4)];
+    let plan = cutting_stock(&demands, 10, 10, PackAlgorithm::Skyline, false, 0.0);
+    assert_eq!(plan.unplaced, vec![(Rectangle { width: 12, height: 2 }, 3)]);
+    assert_eq!(plan.sheets, 1);
+
+    let mut packer = Packer::new();
+    assert!(packer.cutting_plan(0.0).is_none());
+    packer.add_bin(10, 10);
+    packer.add_demand(Rectangle { width: 5, height: 5 }, 8);
+    let plan = packer.cutting_plan(0.0).unwrap();
+    assert_eq!((plan.patterns.len(), plan.sheets), (1, 2));
+}
+
+#[test]
+fn test_kerf_between_pieces() {
+    let demands = [(Rectangle { width: 5, height: 5 }, 8)];
+    // kerfがなければ10x10に4枚入るが、kerfがあると2枚しか並ばない
+    let plan = cutting_stock_with_kerf(&demands, 10, 12, PackAlgorithm::Guillotine, false, 0.0, 2);
+    assert!(plan.unplaced.is_empty());
+    assert!(plan.patterns.iter().all(|p| p.placements.len() == 2));
+    assert_eq!(plan.sheets, 4);
+}
+
+#[test]
+fn test_packer_plan_uses_kerf_and_sheet_material() {
+    let mut packer = Packer::new();
+    let sheet = packer.add_bin(10, 12);
+    packer.set_bin_material(sheet, "oak");
+    packer.set_kerf(2);
+    for _ in 0..4 {
+        let id = packer.add_rect(Rectangle { width: 5, height: 5 });
+        packer.set_rect_material(id, "oak");
+    }
+    let acrylic = packer.add_rect(Rectangle { width: 5, height: 5 });
+    packer.set_rect_material(acrylic, "acrylic");
+    let plan = packer.cutting_plan(0.0).unwrap();
+    // アクリルの矩形は計画に含めない
+    assert_eq!(plan.patterns.iter().map(|p| p.count * p.placements.len()).sum::<usize>(), 4);
+    assert_eq!(plan.sheets, 2);
+}
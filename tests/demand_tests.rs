@@ -0,0 +1,72 @@
+use rustpack::*;
+
+#[test]
+fn test_demand_in_one_bin() {
+    let mut packer = Packer::new();
+    packer.add_bin(2440, 1220);
+    let demand = packer.add_demand(Rectangle { width: 600, height: 400 }, 12);
+    packer.pack();
+    let report = &packer.demand_report()[demand];
+    assert_eq!(report.quantity, 12);
+    assert_eq!(report.placed, 12);
+    assert_eq!(report.bins, vec![(0, 12)]);
+    assert_eq!(report.rect_ids, (0..12).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_demands_across_bins() {
+    let mut packer = Packer::new();
+    packer.add_bin(10, 10);
+    packer.add_bin(10, 10);
+    let single = packer.add_rect(Rectangle { width: 10, height: 5 });
+    let small = packer.add_demand(Rectangle { width: 5, height: 5 }, 4);
+    packer.pack();
+    let reports = packer.demand_report();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[small].placed, 4);
+    assert_eq!(reports[small].bins, vec![(0, 2), (1, 2)]);
+    assert!(!reports[small].rect_ids.contains(&single));
+}
+
+#[test]
+fn test_demand_shortfall() {
+    let mut packer = Packer::new();
+    packer.add_bin(10, 10);
+    let demand = packer.add_demand(Rectangle { width: 5, height: 5 }, 6);
+    packer.pack();
+    let report = &packer.demand_report()[demand];
+    assert_eq!((report.quantity, report.placed), (6, 4));
+    assert_eq!(packer.rect_count(), 6);
+}
+
+#[test]
+fn test_failed_size_does_not_block_smaller_items() {
+    let rects = vec![
+        Rectangle { width: 8, height: 8 },
+        Rectangle { width: 5, height: 5 },
+        Rectangle { width: 5, height: 5 },
+        Rectangle { width: 2, height: 2 },
+        Rectangle { width: 2, height: 2 },
+    ];
+    for place in [maxrects_placement, skyline_placement, guillotine_placement] {
+        let placed = place(&rects, 10, 10, false);
+        assert_eq!(placed.len(), 3);
+        assert!(placed.iter().all(|(r, _, _)| r.width != 5));
+    }
+}
+
+#[test]
+fn test_large_quantity_is_fast() {
+    for algorithm in [PackAlgorithm::MaxRects, PackAlgorithm::Skyline, PackAlgorithm::Guillotine, PackAlgorithm::BottomLeft] {
+        let mut packer = Packer::new();
+        packer.set_pack_algo(algorithm);
+        packer.add_bin(100, 100);
+        let line = packer.add_demand(Rectangle { width: 10, height: 10 }, 3000);
+        let start = std::time::Instant::now();
+        packer.pack();
+        let elapsed = start.elapsed();
+        // 入らないコピーは失敗したサイズとして飛ばされ、対応付けも線形時間で行う
+        assert_eq!(packer.demand_report()[line].placed, 100);
+        assert!(elapsed.as_millis() < 1000, "{:?}: {:?}", algorithm, elapsed);
+    }
+}
@@ -0,0 +1,88 @@
+use rustpack::*;
+
+fn assert_valid(placed: &[(Rectangle, Position, bool)], w: u32, h: u32) {
+    let boxes: Vec<(u32, u32, u32, u32)> = placed.iter().map(|&(r, p, rot)| {
+        let (rw, rh) = if rot { (r.height, r.width) } else { (r.width, r.height) };
+        assert!(p.x + rw <= w && p.y + rh <= h);
+        (p.x, p.y, rw, rh)
+    }).collect();
+    for i in 0..boxes.len() {
+        for j in i + 1..boxes.len() {
+            let (ax, ay, aw, ah) = boxes[i];
+            let (bx, by, bw, bh) = boxes[j];
+            assert!(ax + aw <= bx || bx + bw <= ax || ay + ah <= by || by + bh <= ay);
+        }
+    }
+}
+
+#[test]
+fn test_exact_fit_pinwheel() {
+    let mut rects = vec![Rectangle { width: 6, height: 4 }; 4];
+    rects.push(Rectangle { width: 2, height: 2 });
+    match exact_fit(&rects, 10, 10, true, &ExactLimits::default()) {
+        FitResult::Fits(placed) => {
+            assert_eq!(placed.len(), rects.len());
+            assert_valid(&placed, 10, 10);
+        }
+        other => panic!("expected fit, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_exact_fit_proves_infeasible() {
+    // 面積は足りるが6x6は2つまでしか入らない
+    let rects = vec![Rectangle { width: 6, height: 6 }; 3];
+    assert_eq!(exact_fit(&rects, 12, 9, false, &ExactLimits::default()), FitResult::DoesNotFit);
+}
+
+#[test]
+fn test_exact_min_bins_optimal() {
+    let rects = vec![
+        Rectangle { width: 6, height: 4 },
+        Rectangle { width: 4, height: 6 },
+        Rectangle { width: 6, height: 4 },
+        Rectangle { width: 4, height: 6 },
+        Rectangle { width: 2, height: 2 },
+        Rectangle { width: 5, height: 5 },
+        Rectangle { width: 5, height: 5 },
+        Rectangle { width: 5, height: 5 },
+        Rectangle { width: 5, height: 5 },
+    ];
+    let result = exact_min_bins(&rects, 10, 10, false, &ExactLimits::default());
+    assert!(result.optimal);
+    assert_eq!(result.bins, 2);
+    assert_eq!(result.placements.len(), rects.len());
+    for bin in 0..result.bins {
+        let placed: Vec<_> = result.placements.iter()
+            .filter(|p| p.0 == bin)
+            .map(|&(_, r, p, rot)| (r, p, rot))
+            .collect();
+        assert_valid(&placed, 10, 10);
+    }
+}
+
+#[test]
+fn test_node_limit_reports_best_found() {
+    let rects: Vec<Rectangle> = (0..20).map(|i| Rectangle {
+        width: i % 5 + 3,
+        height: (i * 3) % 7 + 2,
+    }).collect();
+    let limits = ExactLimits { max_nodes: Some(1), time_limit: None };
+    assert_eq!(exact_fit(&rects, 30, 30, true, &limits), FitResult::Unknown);
+    let result = exact_min_bins(&rects, 12, 12, true, &limits);
+    assert!(result.bins >= result.lower_bound);
+    assert_eq!(result.placements.len(), rects.len());
+}
+
+#[test]
+fn test_exact_min_bins_reports_unplaceable() {
+    let mut rects = vec![Rectangle { width: 6, height: 6 }; 3];
+    rects.push(Rectangle { width: 11, height: 2 });
+    rects.push(Rectangle { width: 4, height: 4 });
+    let result = exact_min_bins(&rects, 10, 10, true, &ExactLimits::default());
+    // 6x6は1つのビンに1つしか入らないので3つ必要
+    assert!(result.optimal);
+    assert_eq!(result.bins, 3);
+    assert_eq!(result.unplaced, vec![Rectangle { width: 11, height: 2 }]);
+    assert_eq!(result.placements.len(), 4);
+}
@@ -0,0 +1,51 @@
+use rustpack::*;
+
+fn placed_area(placed: &[(Rectangle, Position, bool)]) -> u32 {
+    placed.iter().map(|(r, _, _)| r.width * r.height).sum()
+}
+
+fn pinwheel() -> Vec<Rectangle> {
+    let mut rects = vec![Rectangle { width: 2, height: 2 }];
+    rects.extend(vec![Rectangle { width: 6, height: 4 }; 4]);
+    rects
+}
+
+#[test]
+fn test_genetic_finds_perfect_pinwheel() {
+    let rects = pinwheel();
+    let config = GeneticConfig { seed: 3, generations: 60, ..Default::default() };
+    let placed = genetic_placement(&rects, 10, 10, skyline_placement, &config);
+    assert_eq!(placed_area(&placed), 100);
+}
+
+#[test]
+fn test_genetic_island_model() {
+    let rects = pinwheel();
+    let config = GeneticConfig {
+        seed: 5,
+        population_size: 12,
+        generations: 40,
+        islands: 3,
+        migration_interval: 5,
+        ..Default::default()
+    };
+    let placed = genetic_placement(&rects, 10, 10, bottom_left_placement, &config);
+    assert_eq!(placed_area(&placed), 100);
+    assert_eq!(placed, genetic_placement(&rects, 10, 10, bottom_left_placement, &config));
+}
+
+#[test]
+fn test_genetic_never_worse_than_greedy() {
+    let rects: Vec<Rectangle> = (0..20).map(|i| Rectangle {
+        width: (i * 7 % 11) + 2,
+        height: (i * 5 % 9) + 2,
+    }).collect();
+    let decoders: [PlacementFn; 2] = [maxrects_placement, guillotine_placement];
+    for decoder in decoders {
+        let greedy = decoder(&rects, 25, 25, false);
+        let config = GeneticConfig { generations: 10, allow_rotate: false, ..Default::default() };
+        let placed = genetic_placement(&rects, 25, 25, decoder, &config);
+        assert!(placed_area(&placed) >= placed_area(&greedy));
+        assert!(placed.iter().all(|&(_, _, rotated)| !rotated));
+    }
+}
@@ -0,0 +1,80 @@
+use rustpack::*;
+
+#[test]
+fn test_matching_grain_blocks_rotation() {
+    let mut packer = Packer::new();
+    packer.enable_rotation();
+    let bin = packer.add_bin(10, 4);
+    packer.set_bin_grain(bin, Grain::Horizontal);
+    let id = packer.add_rect(Rectangle { width: 2, height: 8 });
+    packer.set_rect_grain(id, Grain::Horizontal);
+    packer.pack();
+    assert!(packer.rect_list().is_empty());
+}
+
+#[test]
+fn test_crossed_grain_forces_rotation() {
+    let mut packer = Packer::new();
+    packer.disable_rotation();
+    let bin = packer.add_bin(10, 10);
+    packer.set_bin_grain(bin, Grain::Vertical);
+    let id = packer.add_rect(Rectangle { width: 2, height: 8 });
+    packer.set_rect_grain(id, Grain::Horizontal);
+    packer.pack();
+    let r = packer.rect_list()[0];
+    assert!(r.rotated);
+    assert_eq!((r.width, r.height), (8, 2));
+}
+
+#[test]
+fn test_grain_only_applies_when_both_have_it() {
+    let mut packer = Packer::new();
+    packer.enable_rotation();
+    packer.add_bin(10, 4);
+    let id = packer.add_rect(Rectangle { width: 2, height: 8 });
+    packer.set_rect_grain(id, Grain::Horizontal);
+    packer.pack();
+    assert!(packer.rect_list()[0].rotated);
+}
+
+#[test]
+fn test_free_copy_used_for_rotated_slot() {
+    for algo in PackAlgorithm::ALL {
+        let mut packer = Packer::new();
+        packer.set_pack_algo(algo);
+        packer.enable_rotation();
+        let bin = packer.add_bin(8, 2);
+        packer.set_bin_grain(bin, Grain::Vertical);
+        let fixed = packer.add_rect(Rectangle { width: 2, height: 8 });
+        packer.set_rect_grain(fixed, Grain::Vertical);
+        let free = packer.add_rect(Rectangle { width: 2, height: 8 });
+        packer.pack();
+        let rects = packer.rect_list();
+        assert_eq!(rects.len(), 1);
+        assert_eq!(rects[0].rect_id, free);
+        assert!(rects[0].rotated);
+    }
+}
+
+#[test]
+fn test_stock_grain() {
+    let mut packer = Packer::new();
+    packer.enable_rotation();
+    let stock = packer.add_stock(10, 4, 5, 10.0);
+    packer.set_stock_grain(stock, Grain::Horizontal);
+    let along = packer.add_rect(Rectangle { width: 8, height: 2 });
+    packer.set_rect_grain(along, Grain::Horizontal);
+    let across = packer.add_rect(Rectangle { width: 2, height: 8 });
+    packer.set_rect_grain(across, Grain::Vertical);
+    let blocked = packer.add_rect(Rectangle { width: 2, height: 8 });
+    packer.set_rect_grain(blocked, Grain::Horizontal);
+    packer.pack();
+    let rects = packer.rect_list();
+    // 在庫の木目に合わせて回転が決まり、木目が合わない向きにしか入らない矩形は置かない
+    assert_eq!(rects.len(), 2);
+    let along = rects.iter().find(|r| r.rect_id == along).unwrap();
+    assert!(!along.rotated);
+    let across = rects.iter().find(|r| r.rect_id == across).unwrap();
+    assert!(across.rotated);
+    assert!(packer.bin_list().iter().all(|b| b.stock_id == Some(stock)));
+}
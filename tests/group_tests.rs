@@ -0,0 +1,75 @@
+use rustpack::*;
+
+#[test]
+fn test_group_moves_to_next_bin() {
+    let mut packer = Packer::new();
+    packer.add_rect(Rectangle { width: 10, height: 5 });
+    let a = packer.add_rect_to_group(Rectangle { width: 10, height: 4 }, 0);
+    let b = packer.add_rect_to_group(Rectangle { width: 10, height: 4 }, 0);
+    packer.add_bin(10, 10);
+    packer.add_bin(10, 10);
+
+    packer.pack();
+
+    let rects = packer.rect_list();
+    assert_eq!(rects.len(), 3);
+    // グループは分割されず2番目のビンにまとめて入る
+    let group_bins: Vec<usize> = rects
+        .iter()
+        .filter(|r| r.rect_id == a || r.rect_id == b)
+        .map(|r| r.bin_id)
+        .collect();
+    assert_eq!(group_bins, vec![1, 1]);
+}
+
+#[test]
+fn test_group_too_large_is_not_placed() {
+    let mut packer = Packer::new();
+    for _ in 0..3 {
+        packer.add_rect_to_group(Rectangle { width: 10, height: 4 }, 7);
+    }
+    packer.add_rect(Rectangle { width: 3, height: 3 });
+    packer.add_bin(10, 10);
+    packer.add_bin(10, 10);
+
+    packer.pack();
+
+    let rects = packer.rect_list();
+    assert_eq!(rects.len(), 1);
+    assert_eq!(rects[0].rect_id, 3);
+}
+
+#[test]
+fn test_group_preferred_mode_splits_when_needed() {
+    let mut packer = Packer::new();
+    packer.set_group_mode(GroupMode::Preferred);
+    for _ in 0..3 {
+        packer.add_rect_to_group(Rectangle { width: 10, height: 4 }, 7);
+    }
+    packer.add_bin(10, 10);
+    packer.add_bin(10, 10);
+
+    packer.pack();
+
+    let rects = packer.rect_list();
+    assert_eq!(rects.len(), 3);
+    let bin_ids: std::collections::HashSet<_> = rects.iter().map(|r| r.bin_id).collect();
+    assert_eq!(bin_ids.len(), 2);
+}
+
+#[test]
+fn test_group_preferred_mode_keeps_group_together() {
+    let mut packer = Packer::new();
+    packer.set_group_mode(GroupMode::Preferred);
+    packer.add_rect(Rectangle { width: 10, height: 5 });
+    packer.add_rect_to_group(Rectangle { width: 10, height: 4 }, 0);
+    packer.add_rect_to_group(Rectangle { width: 10, height: 4 }, 0);
+    packer.add_bin(10, 10);
+    packer.add_bin(10, 10);
+
+    packer.pack();
+
+    let rects = packer.rect_list();
+    assert_eq!(rects.len(), 3);
+    assert!(rects.iter().filter(|r| r.rect_id != 0).all(|r| r.bin_id == 1));
+}
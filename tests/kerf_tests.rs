@@ -0,0 +1,62 @@
+use rustpack::*;
+
+fn size(r: &Rectangle, rotated: bool) -> (u32, u32) {
+    if rotated { (r.height, r.width) } else { (r.width, r.height) }
+}
+
+#[test]
+fn test_zero_kerf_matches_guillotine() {
+    let rects: Vec<Rectangle> = (0..15).map(|i| Rectangle { width: i % 5 + 2, height: (i * 3) % 7 + 1 }).collect();
+    assert_eq!(
+        guillotine_placement_with_kerf(&rects, 20, 20, true, 0),
+        guillotine_placement(&rects, 20, 20, true)
+    );
+}
+
+#[test]
+fn test_kerf_not_taken_at_sheet_edge() {
+    // 5 + 3 + 5 + 3 + 5 = 21: 端ではkerfを取らないので3枚ちょうど入る
+    let rects = vec![Rectangle { width: 5, height: 10 }; 3];
+    let placed = guillotine_placement_with_kerf(&rects, 21, 10, false, 3);
+    assert_eq!(placed.len(), 3);
+    let mut xs: Vec<u32> = placed.iter().map(|(_, p, _)| p.x).collect();
+    xs.sort();
+    assert_eq!(xs, vec![0, 8, 16]);
+    // 1つ足りなければ3枚目は入らない
+    assert_eq!(guillotine_placement_with_kerf(&rects, 20, 10, false, 3).len(), 2);
+}
+
+#[test]
+fn test_kerf_separates_items() {
+    let rects: Vec<Rectangle> = (0..20).map(|i| Rectangle { width: i % 4 + 2, height: (i * 5) % 6 + 2 }).collect();
+    let kerf = 2;
+    let placed = guillotine_placement_with_kerf(&rects, 30, 30, true, kerf);
+    for (i, (ra, pa, rota)) in placed.iter().enumerate() {
+        let (aw, ah) = size(ra, *rota);
+        assert!(pa.x + aw <= 30 && pa.y + ah <= 30);
+        for (rb, pb, rotb) in &placed[i + 1..] {
+            let (bw, bh) = size(rb, *rotb);
+            assert!(
+                pa.x + aw + kerf <= pb.x || pb.x + bw + kerf <= pa.x ||
+                pa.y + ah + kerf <= pb.y || pb.y + bh + kerf <= pa.y
+            );
+        }
+    }
+}
+
+#[test]
+fn test_packer_kerf() {
+    for algo in [PackAlgorithm::Guillotine, PackAlgorithm::MaxRects, PackAlgorithm::Skyline] {
+        let mut packer = Packer::new();
+        packer.set_pack_algo(algo);
+        packer.set_kerf(3);
+        for _ in 0..3 {
+            packer.add_rect(Rectangle { width: 5, height: 10 });
+        }
+        packer.add_bin(20, 10);
+        packer.add_bin(21, 10);
+        packer.pack();
+        assert_eq!(packer.rect_list().iter().filter(|r| r.bin_id == 0).count(), 2);
+        assert_eq!(packer.rect_list().iter().filter(|r| r.bin_id == 1).count(), 1);
+    }
+}
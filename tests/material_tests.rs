@@ -0,0 +1,146 @@
+use rustpack::*;
+
+#[test]
+fn test_items_only_use_bins_of_their_material() {
+    let mut packer = Packer::new();
+    let oak = packer.add_bin(10, 10);
+    packer.set_bin_material(oak, "oak-18");
+    let mdf = packer.add_bin(10, 10);
+    packer.set_bin_material(mdf, "mdf-12");
+    let a = packer.add_rect(Rectangle { width: 10, height: 10 });
+    packer.set_rect_material(a, "mdf-12");
+    let b = packer.add_rect(Rectangle { width: 5, height: 5 });
+    packer.set_rect_material(b, "acrylic-3");
+    packer.pack();
+    let rects = packer.rect_list();
+    assert_eq!(rects.len(), 1);
+    assert_eq!((rects[0].rect_id, rects[0].bin_id), (a, mdf));
+}
+
+#[test]
+fn test_pack_by_material_groups_results() {
+    let mut packer = Packer::new();
+    let oak = packer.add_bin(10, 10);
+    packer.set_bin_material(oak, "oak");
+    let plain = packer.add_bin(10, 10);
+    let mut oak_rects = Vec::new();
+    for _ in 0..2 {
+        let id = packer.add_rect(Rectangle { width: 5, height: 10 });
+        packer.set_rect_material(id, "oak");
+        oak_rects.push(id);
+    }
+    let untagged = packer.add_rect(Rectangle { width: 4, height: 4 });
+    let acrylic = packer.add_rect(Rectangle { width: 4, height: 4 });
+    packer.set_rect_material(acrylic, "acrylic");
+
+    let pools = packer.pack_by_material();
+    let keys: Vec<Option<&str>> = pools.iter().map(|p| p.material.as_deref()).collect();
+    assert_eq!(keys, vec![None, Some("acrylic"), Some("oak")]);
+
+    assert_eq!(pools[0].rects.len(), 1);
+    assert_eq!((pools[0].rects[0].rect_id, pools[0].rects[0].bin_id), (untagged, plain));
+    // アクリルのビンがないので配置できない
+    assert!(pools[1].rects.is_empty() && pools[1].bins.is_empty());
+    assert_eq!(pools[1].unplaced, vec![acrylic]);
+    let mut placed: Vec<usize> = pools[2].rects.iter().map(|r| r.rect_id).collect();
+    placed.sort();
+    assert_eq!(placed, oak_rects);
+    assert_eq!(pools[2].bins.len(), 1);
+    assert_eq!(pools[2].bins[0].bin_id, oak);
+    // 全体の結果にも反映される
+    assert_eq!(packer.rect_list().len(), 3);
+}
+
+#[test]
+fn test_stock_per_material_gets_distinct_bin_ids() {
+    let mut packer = Packer::new();
+    let oak = packer.add_stock(10, 10, 5, 50.0);
+    packer.set_stock_material(oak, "oak");
+    let mdf = packer.add_stock(10, 10, 5, 20.0);
+    packer.set_stock_material(mdf, "mdf");
+    let line = packer.add_demand(Rectangle { width: 10, height: 10 }, 2);
+    packer.set_demand_material(line, "oak");
+    let board = packer.add_rect(Rectangle { width: 10, height: 10 });
+    packer.set_rect_material(board, "mdf");
+
+    let pools = packer.pack_by_material();
+    assert_eq!(pools.len(), 2);
+    let bins = packer.bin_list();
+    assert_eq!(bins.len(), 3);
+    let mut ids: Vec<usize> = packer.rect_list().iter().map(|r| r.bin_id).collect();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), 3);
+    let mdf_pool = pools.iter().find(|p| p.material.as_deref() == Some("mdf")).unwrap();
+    assert_eq!(mdf_pool.bins.len(), 1);
+    assert_eq!(mdf_pool.bins[0].stock_id, Some(mdf));
+    assert_eq!(packer.total_cost(), 120.0);
+}
+
+#[test]
+fn test_without_materials_matches_pack() {
+    let mut packer = Packer::new();
+    packer.add_bin(10, 10);
+    packer.add_bin(10, 10);
+    for _ in 0..5 {
+        packer.add_rect(Rectangle { width: 5, height: 5 });
+    }
+    packer.pack();
+    let expected = packer.rect_list().to_vec();
+    let pools = packer.pack_by_material();
+    assert_eq!(pools.len(), 1);
+    assert_eq!(pools[0].material, None);
+    assert_eq!(packer.rect_list(), expected.as_slice());
+}
+
+#[test]
+fn test_required_group_across_materials_is_not_split() {
+    let mut packer = Packer::new();
+    let oak = packer.add_bin(10, 10);
+    packer.set_bin_material(oak, "oak");
+    let mdf = packer.add_bin(10, 10);
+    packer.set_bin_material(mdf, "mdf");
+    let a = packer.add_rect_to_group(Rectangle { width: 5, height: 5 }, 0);
+    packer.set_rect_material(a, "oak");
+    let b = packer.add_rect_to_group(Rectangle { width: 5, height: 5 }, 0);
+    packer.set_rect_material(b, "mdf");
+    let single = packer.add_rect(Rectangle { width: 5, height: 5 });
+    packer.set_rect_material(single, "oak");
+
+    packer.pack();
+    assert_eq!(packer.rect_list().iter().map(|r| r.rect_id).collect::<Vec<_>>(), vec![single]);
+    // 素材ごとに分けても、グループの一部だけを配置しない
+    let pools = packer.pack_by_material();
+    assert_eq!(packer.rect_list().iter().map(|r| r.rect_id).collect::<Vec<_>>(), vec![single]);
+    let mdf_pool = pools.iter().find(|p| p.material.as_deref() == Some("mdf")).unwrap();
+    assert_eq!(mdf_pool.unplaced, vec![b]);
+
+    // Preferredモードでは素材ごとに分割してよい
+    packer.set_group_mode(GroupMode::Preferred);
+    packer.pack_by_material();
+    assert_eq!(packer.rect_list().len(), 3);
+}
+
+#[test]
+fn test_bin_ids_stable_after_pack_by_material() {
+    let mut packer = Packer::new();
+    packer.set_sort_order(SortOrder::Area);
+    let oak = packer.add_stock(10, 10, 5, 1.0);
+    packer.set_stock_material(oak, "oak");
+    for _ in 0..2 {
+        let id = packer.add_rect(Rectangle { width: 10, height: 10 });
+        packer.set_rect_material(id, "oak");
+    }
+    packer.pack_by_material();
+    assert_eq!(packer.bin_list().len(), 2);
+
+    let big = packer.add_bin(20, 20);
+    assert_eq!(big, 0);
+    packer.set_bin_material(big, "oak");
+    let rect = packer.add_rect(Rectangle { width: 15, height: 15 });
+    packer.set_rect_material(rect, "oak");
+    packer.pin_to_bin(rect, big);
+    packer.pack_by_material();
+    let placed = packer.rect_list().iter().find(|r| r.rect_id == rect).unwrap();
+    assert_eq!(placed.bin_id, big);
+}
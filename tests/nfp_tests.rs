@@ -0,0 +1,70 @@
+use rustpack::*;
+
+fn triangle() -> PolygonPart {
+    PolygonPart::new(vec![
+        Position { x: 0, y: 0 },
+        Position { x: 10, y: 0 },
+        Position { x: 0, y: 10 },
+    ], 1)
+}
+
+#[test]
+fn test_nfp_squares_touch() {
+    let square = PolygonPart::new(vec![
+        Position { x: 0, y: 0 },
+        Position { x: 5, y: 0 },
+        Position { x: 5, y: 5 },
+        Position { x: 0, y: 5 },
+    ], 1);
+    let parts = vec![square; 5];
+    let placed = nfp_placement(&parts, 10, 10);
+    assert_eq!(placed.len(), 4);
+    let positions: Vec<(f64, f64)> = placed.iter().map(|p| (p.x, p.y)).collect();
+    assert_eq!(positions, vec![(0.0, 0.0), (5.0, 0.0), (0.0, 5.0), (5.0, 5.0)]);
+}
+
+#[test]
+fn test_nfp_triangles_need_rotation() {
+    let parts = vec![triangle(), triangle()];
+    assert_eq!(nfp_placement(&parts, 10, 10).len(), 1);
+
+    // 180度回転を許すと2つの三角形が噛み合う
+    let mut rotatable = triangle();
+    rotatable.rotation_steps = 2;
+    let parts = vec![rotatable.clone(), rotatable];
+    let placed = nfp_placement(&parts, 10, 10);
+    assert_eq!(placed.len(), 2);
+    assert_eq!(placed[1].rotation, 180.0);
+    assert_eq!((placed[1].x, placed[1].y), (0.0, 0.0));
+}
+
+#[test]
+fn test_nfp_concave_parts_stay_in_bin() {
+    // L字型部品
+    let l_shape = PolygonPart::new(vec![
+        Position { x: 0, y: 0 },
+        Position { x: 6, y: 0 },
+        Position { x: 6, y: 2 },
+        Position { x: 2, y: 2 },
+        Position { x: 2, y: 6 },
+        Position { x: 0, y: 6 },
+    ], 4);
+    let parts = vec![l_shape; 4];
+    let placed = nfp_placement(&parts, 10, 10);
+    assert!(placed.len() >= 3);
+    for p in &placed {
+        for (x, y) in parts[p.part_index].placed_vertices(p) {
+            assert!((-1e-6..=10.0 + 1e-6).contains(&x));
+            assert!((-1e-6..=10.0 + 1e-6).contains(&y));
+        }
+    }
+}
+
+#[test]
+fn test_nest_parts_multiple_bins() {
+    let parts = vec![triangle(), triangle(), triangle()];
+    let placed = nest_parts(&parts, &[(10, 10), (10, 10), (10, 10)]);
+    assert_eq!(placed.len(), 3);
+    let bins: Vec<usize> = placed.iter().map(|p| p.bin_id).collect();
+    assert_eq!(bins, vec![0, 1, 2]);
+}
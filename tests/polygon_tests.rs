@@ -0,0 +1,79 @@
+use rustpack::*;
+
+fn assert_valid(placed: &[(Rectangle, Position, bool)], bin: &PolygonBin) {
+    for (i, &(r, p, rot)) in placed.iter().enumerate() {
+        let (w, h) = if rot { (r.height, r.width) } else { (r.width, r.height) };
+        assert!(bin.contains_rect(p, w, h));
+        for &(r2, p2, rot2) in &placed[i + 1..] {
+            let (w2, h2) = if rot2 { (r2.height, r2.width) } else { (r2.width, r2.height) };
+            assert!(p.x + w <= p2.x || p2.x + w2 <= p.x || p.y + h <= p2.y || p2.y + h2 <= p.y);
+        }
+    }
+}
+
+#[test]
+fn test_contains_rect_triangle() {
+    let bin = PolygonBin::new(vec![
+        Position { x: 0, y: 0 },
+        Position { x: 10, y: 0 },
+        Position { x: 0, y: 10 },
+    ]);
+    // 斜辺に角が接する場合は収まる
+    assert!(bin.contains_rect(Position { x: 0, y: 0 }, 5, 5));
+    assert!(!bin.contains_rect(Position { x: 0, y: 0 }, 6, 5));
+    assert!(!bin.contains_rect(Position { x: 5, y: 5 }, 1, 1));
+    assert!(bin.contains_rect(Position { x: 2, y: 2 }, 3, 3));
+}
+
+#[test]
+fn test_contains_rect_concave() {
+    // L字型のビン
+    let bin = PolygonBin::new(vec![
+        Position { x: 0, y: 0 },
+        Position { x: 10, y: 0 },
+        Position { x: 10, y: 4 },
+        Position { x: 4, y: 4 },
+        Position { x: 4, y: 10 },
+        Position { x: 0, y: 10 },
+    ]);
+    assert!(bin.contains_rect(Position { x: 0, y: 0 }, 10, 4));
+    assert!(bin.contains_rect(Position { x: 0, y: 0 }, 4, 10));
+    assert!(!bin.contains_rect(Position { x: 0, y: 0 }, 5, 5));
+    assert!(!bin.contains_rect(Position { x: 5, y: 5 }, 2, 2));
+}
+
+#[test]
+fn test_polygon_bottom_left_diagonal_cut() {
+    // 右上が斜めにカットされた端材
+    let bin = PolygonBin::new(vec![
+        Position { x: 0, y: 0 },
+        Position { x: 20, y: 0 },
+        Position { x: 20, y: 10 },
+        Position { x: 10, y: 20 },
+        Position { x: 0, y: 20 },
+    ]);
+    let rects = vec![Rectangle { width: 10, height: 10 }; 4];
+    let placed = polygon_bottom_left_placement(&rects, &bin, false);
+    assert_eq!(placed.len(), 3);
+    assert_eq!(placed[0].1, Position { x: 0, y: 0 });
+    assert_eq!(placed[1].1, Position { x: 10, y: 0 });
+    assert_eq!(placed[2].1, Position { x: 0, y: 10 });
+    assert_valid(&placed, &bin);
+}
+
+#[test]
+fn test_polygon_bottom_left_with_rotate() {
+    let bin = PolygonBin::new(vec![
+        Position { x: 0, y: 0 },
+        Position { x: 12, y: 0 },
+        Position { x: 0, y: 12 },
+    ]);
+    let rects = vec![
+        Rectangle { width: 2, height: 9 },
+        Rectangle { width: 3, height: 3 },
+        Rectangle { width: 2, height: 2 },
+    ];
+    let placed = polygon_bottom_left_placement(&rects, &bin, true);
+    assert_eq!(placed.len(), 3);
+    assert_valid(&placed, &bin);
+}
@@ -0,0 +1,83 @@
+use rustpack::*;
+use std::collections::HashSet;
+
+fn sample_packer() -> Packer {
+    let mut packer = Packer::new();
+    for i in 0..40 {
+        packer.add_rect(Rectangle { width: (i % 9) * 3 + 4, height: (i % 5) * 4 + 3 });
+    }
+    for _ in 0..6 {
+        packer.add_bin(50, 50);
+    }
+    packer
+}
+
+fn bins_used(packer: &Packer) -> usize {
+    packer.rect_list().iter().map(|r| r.bin_id).collect::<HashSet<_>>().len()
+}
+
+#[test]
+fn test_sort_order() {
+    let mut packer = Packer::new();
+    packer.add_rect(Rectangle { width: 2, height: 2 });
+    let big = packer.add_rect(Rectangle { width: 5, height: 5 });
+    packer.add_bin(10, 10);
+    packer.set_sort_order(SortOrder::Area);
+    packer.pack();
+
+    let rects = packer.rect_list();
+    assert_eq!(rects[0].rect_id, big);
+    assert_eq!((rects[0].x, rects[0].y), (0, 0));
+}
+
+#[test]
+fn test_pack_best_beats_every_configuration() {
+    let mut best = sample_packer();
+    let config = best.pack_best(Objective::BinsUsed);
+    let best_placed = best.rect_list().len();
+    let best_bins = bins_used(&best);
+
+    for candidate in PackConfig::all() {
+        let mut packer = sample_packer();
+        packer.set_pack_algo(candidate.algorithm);
+        packer.set_sort_order(candidate.sort_order);
+        if candidate.allow_rotation {
+            packer.enable_rotation();
+        }
+        packer.pack();
+        let placed = packer.rect_list().len();
+        assert!(placed < best_placed || (placed == best_placed && bins_used(&packer) >= best_bins));
+        if candidate == config {
+            assert_eq!(packer.rect_list(), best.rect_list());
+        }
+    }
+}
+
+#[test]
+fn test_pack_best_bounding_area() {
+    let mut packer = Packer::new();
+    packer.add_rect(Rectangle { width: 10, height: 2 });
+    packer.add_rect(Rectangle { width: 2, height: 10 });
+    packer.add_bin(20, 20);
+
+    let config = packer.pack_best(Objective::BoundingArea);
+    let bin = packer.bin_list()[0];
+    assert_eq!(packer.rect_list().len(), 2);
+    // 回転して並べれば 10x4 に収まる
+    assert_eq!(bin.used_width as u64 * bin.used_height as u64, 40);
+    assert!(config.allow_rotation);
+}
+
+#[test]
+fn test_configs_use_distinct_placements() {
+    let configs = PackConfig::all();
+    // BottomLeft・MaxRects・Skyline・Guillotineの4つの配置関数だけを試す
+    assert_eq!(configs.len(), 4 * SortOrder::ALL.len() * 2);
+    for (i, a) in configs.iter().enumerate() {
+        for b in &configs[i + 1..] {
+            assert!((a.algorithm.placement(), a.sort_order, a.allow_rotation) != (b.algorithm.placement(), b.sort_order, b.allow_rotation));
+        }
+    }
+    assert_eq!(PackAlgorithm::MaxRectsBaf.placement(), PackAlgorithm::MaxRects);
+    assert_eq!(PackAlgorithm::SkylineBl.placement(), PackAlgorithm::Skyline);
+}
@@ -0,0 +1,51 @@
+use rustpack::*;
+
+#[test]
+fn test_masks_interlock() {
+    let lower = OccupancyMask::from_fn(4, 4, |x, y| x + y < 4);
+    let upper = OccupancyMask::from_fn(4, 4, |x, y| x + y >= 4);
+
+    let placed = mask_placement(&[lower, upper], 4, 4, false);
+    assert_eq!(placed.len(), 2);
+    assert_eq!(placed[0].1, Position { x: 0, y: 0 });
+    assert_eq!(placed[1].1, Position { x: 0, y: 0 });
+    assert_eq!(placed[1].0, Rectangle { width: 4, height: 4 });
+}
+
+#[test]
+fn test_full_masks_behave_like_rectangles() {
+    let masks = vec![OccupancyMask::full(3, 3), OccupancyMask::full(3, 3)];
+    let placed = mask_placement(&masks, 5, 5, false);
+    // 通常の矩形と同様に1つしか入らない
+    assert_eq!(placed.len(), 1);
+}
+
+#[test]
+fn test_mask_rotation() {
+    let mask = OccupancyMask::from_fn(3, 1, |x, _| x != 1);
+    let rotated = mask.rotated();
+    assert_eq!((rotated.width, rotated.height), (1, 3));
+    assert!(rotated.is_opaque(0, 0));
+    assert!(!rotated.is_opaque(0, 1));
+
+    let placed = mask_placement(&[OccupancyMask::full(5, 2)], 2, 5, true);
+    assert_eq!(placed.len(), 1);
+    assert!(placed[0].2);
+}
+
+#[test]
+fn test_new_rejects_bad_cells() {
+    assert!(OccupancyMask::new(2, 2, vec![true; 3]).is_none());
+    assert!(OccupancyMask::new(u32::MAX, u32::MAX, Vec::new()).is_none());
+    let mask = OccupancyMask::new(2, 1, vec![true, false]).unwrap();
+    assert!(mask.is_opaque(0, 0) && !mask.is_opaque(1, 0));
+}
+
+#[test]
+fn test_large_container() {
+    // 1000x1000に100x100を100個並べても、ブロックを飛ばして走査するので現実的な時間で終わる
+    let masks = vec![OccupancyMask::full(100, 100); 100];
+    let placed = mask_placement(&masks, 1000, 1000, false);
+    assert_eq!(placed.len(), 100);
+    assert_eq!(placed[99].1, Position { x: 900, y: 900 });
+}
@@ -0,0 +1,185 @@
+use rustpack::*;
+
+#[test]
+fn test_text_round_trip() {
+    let mut store = RemnantStore::new(5, 5);
+    let a = store.add(10, 6);
+    let b = store.add(7, 20);
+    store.remove(a);
+    let loaded = RemnantStore::from_text(&store.to_text()).unwrap();
+    assert_eq!(loaded, store);
+    // 削除したIDは再利用しない
+    let mut loaded = loaded;
+    assert!(loaded.add(8, 8) > b);
+    assert!(RemnantStore::from_text("remnant 0 1 1\n").is_err());
+    assert!(RemnantStore::from_text("rustpack-remnants 1\nremnant 0 x 1\n").is_err());
+}
+
+#[test]
+fn test_save_and_load() {
+    let path = std::env::temp_dir().join(format!("rustpack-remnants-{}.txt", std::process::id()));
+    let mut store = RemnantStore::new(3, 3);
+    store.add(12, 4);
+    store.save(&path).unwrap();
+    let loaded = RemnantStore::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded, store);
+}
+
+#[test]
+fn test_job_feeds_next_job() {
+    let mut store = RemnantStore::new(5, 5);
+
+    // 1日目: 10x10のシートから10x4を切り、10x6の端材が残る
+    let mut today = Packer::new();
+    today.add_bin(10, 10);
+    today.add_rect(Rectangle { width: 10, height: 4 });
+    today.pack_with_remnants(&mut store);
+    assert!(!today.rect_list()[0].in_remnant);
+    assert_eq!(store.remnants().len(), 1);
+    let leftover = store.remnants()[0].clone();
+    assert_eq!((leftover.width, leftover.height), (10, 6));
+
+    // 2日目: 新しいシートより先に端材を使う
+    let mut tomorrow = Packer::new();
+    let sheet = tomorrow.add_bin(10, 10);
+    tomorrow.add_rect(Rectangle { width: 5, height: 5 });
+    tomorrow.pack_with_remnants(&mut store);
+    let rect = tomorrow.rect_list()[0];
+    assert!(rect.in_remnant);
+    assert_ne!(rect.bin_id, sheet);
+    let bins = tomorrow.bin_list();
+    assert_eq!(bins.len(), 1);
+    assert_eq!(bins[0].remnant_id, Some(leftover.id));
+    // 使った端材は取り除かれ、その残り(5x6)が保管される
+    assert!(store.get(leftover.id).is_none());
+    assert_eq!(store.remnants().len(), 1);
+    assert_eq!((store.remnants()[0].width, store.remnants()[0].height), (5, 6));
+}
+
+#[test]
+fn test_unused_remnants_stay_in_store() {
+    let mut store = RemnantStore::new(1, 1);
+    let small = store.add(2, 2);
+    let mut packer = Packer::new();
+    packer.add_bin(10, 10);
+    packer.add_rect(Rectangle { width: 10, height: 10 });
+    packer.pack_with_remnants(&mut store);
+    assert!(!packer.rect_list()[0].in_remnant);
+    assert!(store.get(small).is_some());
+    assert_eq!(store.len(), 1);
+    // 再実行しても端材のビンは重複しない
+    packer.pack();
+    assert_eq!(packer.bin_list().len(), 1);
+}
+
+#[test]
+fn test_offers_are_consumed_by_update() {
+    let mut store = RemnantStore::new(1, 1);
+    let offcut = store.add(5, 5);
+    let mut packer = Packer::new();
+    packer.add_bin(10, 10);
+    packer.add_rect(Rectangle { width: 5, height: 5 });
+    packer.pack_with_remnants(&mut store);
+    assert!(packer.rect_list()[0].in_remnant);
+    assert!(store.get(offcut).is_none());
+    // 保管庫から取り除いた端材は、次の通常の実行では使わない
+    packer.pack();
+    assert!(!packer.rect_list()[0].in_remnant);
+    assert!(packer.bin_list().iter().all(|b| b.remnant_id.is_none()));
+}
+
+#[test]
+fn test_remnants_keep_material_and_grain() {
+    let mut store = RemnantStore::new(5, 5);
+    let mut today = Packer::new();
+    let sheet = today.add_bin(10, 10);
+    today.set_bin_material(sheet, "oak 18mm");
+    today.set_bin_grain(sheet, Grain::Horizontal);
+    let id = today.add_rect(Rectangle { width: 10, height: 4 });
+    today.set_rect_material(id, "oak 18mm");
+    today.pack_with_remnants(&mut store);
+    let offcut = store.remnants()[0].clone();
+    assert_eq!(offcut.material.as_deref(), Some("oak 18mm"));
+    assert_eq!(offcut.grain, Some(Grain::Horizontal));
+    let loaded = RemnantStore::from_text(&store.to_text()).unwrap();
+    assert_eq!(loaded, store);
+
+    // 素材のない矩形はオークの端材に置かない
+    let mut tomorrow = Packer::new();
+    tomorrow.enable_rotation();
+    tomorrow.add_bin(10, 10);
+    let plain = tomorrow.add_rect(Rectangle { width: 5, height: 5 });
+    let oak = tomorrow.add_rect(Rectangle { width: 2, height: 6 });
+    tomorrow.set_rect_material(oak, "oak 18mm");
+    tomorrow.set_rect_grain(oak, Grain::Vertical);
+    tomorrow.offer_remnants(&store);
+    tomorrow.pack();
+    let rects = tomorrow.rect_list();
+    let plain = rects.iter().find(|r| r.rect_id == plain).unwrap();
+    assert!(!plain.in_remnant);
+    // オークの矩形は端材に、木目を合わせて回転して置かれる
+    let oak = rects.iter().find(|r| r.rect_id == oak).unwrap();
+    assert!(oak.in_remnant && oak.rotated);
+}
+
+#[test]
+fn test_loads_version_one_files() {
+    let store = RemnantStore::from_text("rustpack-remnants 1\nmin 5 5\nnext 3\nremnant 2 10 6\n").unwrap();
+    assert_eq!(store.remnants().len(), 1);
+    assert_eq!(store.get(2).unwrap().material, None);
+    assert!(RemnantStore::from_text("rustpack-remnants 2\nremnant 0 1 1 diagonal\n").is_err());
+}
+
+#[test]
+fn test_bin_ids_stable_with_remnants() {
+    let mut store = RemnantStore::new(1, 1);
+    store.add(5, 5);
+    let mut packer = Packer::new();
+    packer.set_sort_order(SortOrder::Area);
+    packer.add_bin(10, 10);
+    packer.add_rect(Rectangle { width: 5, height: 5 });
+    packer.offer_remnants(&store);
+    packer.pack();
+    assert!(packer.rect_list()[0].in_remnant);
+
+    // 端材のビンの後に追加したビンも、次のpackで同じIDのまま
+    let big = packer.add_bin(20, 20);
+    assert_eq!(big, 1);
+    let rect = packer.add_rect(Rectangle { width: 15, height: 15 });
+    packer.pin_to_bin(rect, big);
+    packer.pack();
+    let placed = packer.rect_list().iter().find(|r| r.rect_id == rect).unwrap();
+    assert_eq!(placed.bin_id, big);
+}
+
+#[test]
+fn test_text_parsing_tolerates_spacing_and_normalises_materials() {
+    let text = "rustpack-remnants 2\nmin  5\t5\nnext 3\nremnant  0   10 6  horizontal   oak  18mm \nremnant 1 4 4 -\n";
+    let store = RemnantStore::from_text(text).unwrap();
+    assert_eq!(store.min_size(), (5, 5));
+    assert_eq!(store.get(0).unwrap().material.as_deref(), Some("oak 18mm"));
+    assert_eq!(store.get(0).unwrap().grain, Some(Grain::Horizontal));
+    assert_eq!(store.get(1).unwrap().material, None);
+    assert!(RemnantStore::from_text("rustpack-remnants 2\nmin 5 5 5\n").is_err());
+
+    // 前後の空白や空の素材も読み戻して同じになる
+    let mut store = RemnantStore::new(1, 1);
+    let a = store.add(3, 3);
+    let b = store.add(4, 4);
+    store.set_material(a, "  birch\n ply ");
+    store.set_material(b, "   ");
+    assert_eq!(store.get(a).unwrap().material.as_deref(), Some("birch ply"));
+    assert_eq!(store.get(b).unwrap().material, None);
+    assert_eq!(RemnantStore::from_text(&store.to_text()).unwrap(), store);
+
+    // Packerの素材も同じ規則で揃うので、空白の違いで別の素材にならない
+    let mut packer = Packer::new();
+    let sheet = packer.add_bin(10, 10);
+    packer.set_bin_material(sheet, "birch ply ");
+    let rect = packer.add_rect(Rectangle { width: 2, height: 2 });
+    packer.set_rect_material(rect, " birch  ply");
+    packer.pack();
+    assert_eq!(packer.rect_list().len(), 1);
+    assert_eq!(packer.materials(), vec![Some("birch ply".to_string())]);
+}
@@ -0,0 +1,62 @@
+use rustpack::*;
+
+fn overlaps(a: (u32, u32, u32, u32), b: (u32, u32, u32, u32)) -> bool {
+    a.0 < b.0 + b.2 && b.0 < a.0 + a.2 && a.1 < b.1 + b.3 && b.1 < a.1 + a.3
+}
+
+fn boxed(r: &GuillotineRect) -> (u32, u32, u32, u32) {
+    (r.position.x, r.position.y, r.width, r.height)
+}
+
+#[test]
+fn test_corner_item_remnants() {
+    let placed = vec![(Rectangle { width: 2, height: 2 }, Position { x: 0, y: 0 }, false)];
+    let remnants = find_remnants(&placed, 10, 10, 1, 1, 0);
+    let boxes: Vec<_> = remnants.iter().map(boxed).collect();
+    assert_eq!(boxes, vec![(0, 2, 10, 8), (2, 0, 8, 2)]);
+}
+
+#[test]
+fn test_min_size_filter() {
+    let placed = vec![(Rectangle { width: 2, height: 2 }, Position { x: 0, y: 0 }, false)];
+    let remnants = find_remnants(&placed, 10, 10, 3, 3, 0);
+    assert_eq!(remnants.len(), 1);
+    // 回転すれば満たすものも含める
+    assert_eq!(find_remnants(&placed, 10, 10, 2, 8, 0).len(), 2);
+}
+
+#[test]
+fn test_remnants_are_disjoint_and_cuttable() {
+    let rects: Vec<Rectangle> = (0..12).map(|i| Rectangle { width: (i * 7) % 9 + 2, height: (i * 5) % 6 + 2 }).collect();
+    for kerf in [0, 2] {
+        let placed = guillotine_placement_with_kerf(&rects, 40, 40, true, kerf);
+        let remnants = find_remnants(&placed, 40, 40, 2, 2, kerf);
+        assert!(!remnants.is_empty());
+        let mut all: Vec<(Rectangle, Position, bool)> = placed.clone();
+        for r in &remnants {
+            assert!(r.width >= 2 && r.height >= 2);
+            assert!(r.position.x + r.width <= 40 && r.position.y + r.height <= 40);
+            for &(rect, pos, rot) in &placed {
+                let (w, h) = if rot { (rect.height, rect.width) } else { (rect.width, rect.height) };
+                assert!(!overlaps(boxed(r), (pos.x, pos.y, w, h)));
+            }
+            all.push((Rectangle { width: r.width, height: r.height }, r.position, false));
+        }
+        // 端材を部品とみなしても切断木が作れる
+        assert!(cut_tree(&all, 40, 40, kerf).is_some());
+    }
+}
+
+#[test]
+fn test_packer_remnants() {
+    let mut packer = Packer::new();
+    packer.add_bin(10, 10);
+    packer.add_bin(10, 10);
+    packer.add_rect(Rectangle { width: 10, height: 4 });
+    packer.pack();
+    let remnants = packer.remnants(5, 5);
+    assert_eq!(remnants, vec![Remnant {
+        bin_id: 0,
+        rect: GuillotineRect { position: Position { x: 0, y: 4 }, width: 10, height: 6 },
+    }]);
+}
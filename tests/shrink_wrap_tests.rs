@@ -0,0 +1,50 @@
+use rustpack::*;
+
+fn packer_with_two_bins() -> Packer {
+    let mut packer = Packer::new();
+    packer.set_pack_algo(PackAlgorithm::Skyline);
+    packer.add_rect(Rectangle { width: 300, height: 200 });
+    packer.add_rect(Rectangle { width: 100, height: 120 });
+    packer.add_rect(Rectangle { width: 900, height: 900 });
+    packer.add_bin(1024, 1024);
+    packer.add_bin(1024, 1024);
+    packer.add_bin(1024, 1024);
+    packer
+}
+
+#[test]
+fn test_bin_list_exact() {
+    let mut packer = packer_with_two_bins();
+    packer.pack();
+
+    let bins = packer.bin_list();
+    // 3番目のビンは使われていない
+    assert_eq!(bins.len(), 2);
+    assert_eq!((bins[0].used_width, bins[0].used_height), (400, 200));
+    assert_eq!(bins[0].rect_count, 2);
+    assert_eq!((bins[1].bin_id, bins[1].used_width, bins[1].used_height), (1, 900, 900));
+}
+
+#[test]
+fn test_bin_list_power_of_two() {
+    let mut packer = packer_with_two_bins();
+    packer.set_size_rounding(SizeRounding::PowerOfTwo);
+    packer.pack();
+
+    let bins = packer.bin_list();
+    assert_eq!((bins[0].used_width, bins[0].used_height), (512, 256));
+    assert_eq!((bins[1].used_width, bins[1].used_height), (1024, 1024));
+}
+
+#[test]
+fn test_bin_list_multiple_of_clamped() {
+    let mut packer = Packer::new();
+    packer.add_rect(Rectangle { width: 33, height: 95 });
+    packer.add_bin(40, 100);
+    packer.set_size_rounding(SizeRounding::MultipleOf(16));
+    packer.pack();
+
+    let bins = packer.bin_list();
+    // 切り上げてもビンの大きさは超えない
+    assert_eq!((bins[0].used_width, bins[0].used_height), (40, 96));
+}
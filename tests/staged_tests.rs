@@ -0,0 +1,139 @@
+use rustpack::*;
+
+fn boxes(placed: &[(Rectangle, Position, bool)]) -> Vec<(u32, u32, u32, u32)> {
+    placed.iter().map(|&(r, p, rot)| {
+        let (w, h) = if rot { (r.height, r.width) } else { (r.width, r.height) };
+        (p.x, p.y, w, h)
+    }).collect()
+}
+
+fn assert_valid(placed: &[(Rectangle, Position, bool)], w: u32, h: u32) {
+    let b = boxes(placed);
+    for (i, &(ax, ay, aw, ah)) in b.iter().enumerate() {
+        assert!(ax + aw <= w && ay + ah <= h);
+        for &(bx, by, bw, bh) in &b[i + 1..] {
+            assert!(ax + aw <= bx || bx + bw <= ax || ay + ah <= by || by + bh <= ay);
+        }
+    }
+}
+
+fn instance() -> Vec<Rectangle> {
+    (0..30).map(|i| Rectangle { width: (i * 7) % 9 + 2, height: (i * 5) % 4 + 2 }).collect()
+}
+
+#[test]
+fn test_two_stage_exact_strips() {
+    let rects = instance();
+    let placed = staged_guillotine_placement(&rects, 30, 30, true, &StageLimit::two_stage(false));
+    assert!(!placed.is_empty());
+    assert_valid(&placed, 30, 30);
+    // 同じストリップの部品は高さが等しく、ストリップは重ならない
+    let b = boxes(&placed);
+    for &(_, ay, _, ah) in &b {
+        for &(_, by, _, bh) in &b {
+            if ay == by {
+                assert_eq!(ah, bh);
+            } else {
+                assert!(ay + ah <= by || by + bh <= ay);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_three_stage_columns_share_width() {
+    let rects = instance();
+    let placed = staged_guillotine_placement(&rects, 30, 30, true, &StageLimit::three_stage(false));
+    assert_valid(&placed, 30, 30);
+    let b = boxes(&placed);
+    // ストリップの境界はどの部品も横切らない左端の部品の下端
+    let starts: Vec<u32> = b.iter()
+        .filter(|s| s.0 == 0 && !b.iter().any(|o| o.1 < s.1 && s.1 < o.1 + o.3))
+        .map(|s| s.1)
+        .collect();
+    let strip_of = |y: u32| starts.iter().copied().filter(|&s| s <= y).max().unwrap();
+    for &(ax, ay, aw, _) in &b {
+        for &(bx, by, bw, _) in &b {
+            if ax == bx && strip_of(ay) == strip_of(by) {
+                assert_eq!(aw, bw);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_more_stages_place_more() {
+    let rects = vec![
+        Rectangle { width: 4, height: 4 },
+        Rectangle { width: 4, height: 2 },
+        Rectangle { width: 4, height: 2 },
+    ];
+    assert_eq!(staged_guillotine_placement(&rects, 8, 4, false, &StageLimit::two_stage(false)).len(), 1);
+    assert_eq!(staged_guillotine_placement(&rects, 8, 4, false, &StageLimit::two_stage(true)).len(), 2);
+    let placed = staged_guillotine_placement(&rects, 8, 4, false, &StageLimit::three_stage(false));
+    assert_eq!(placed.len(), 3);
+    assert_valid(&placed, 8, 4);
+}
+
+#[test]
+fn test_packer_stage_limit() {
+    let mut packer = Packer::new();
+    packer.set_pack_algo(PackAlgorithm::Guillotine);
+    packer.set_guillotine_stages(Some(StageLimit::two_stage(false)));
+    packer.add_rect(Rectangle { width: 4, height: 4 });
+    packer.add_rect(Rectangle { width: 4, height: 2 });
+    packer.add_bin(8, 4);
+    packer.add_bin(8, 4);
+    packer.pack();
+    let rects = packer.rect_list();
+    assert_eq!(rects.len(), 2);
+    assert_ne!(rects[0].bin_id, rects[1].bin_id);
+}
+
+#[test]
+fn test_preferred_groups_keep_placed_items() {
+    let mut packer = Packer::new();
+    packer.set_pack_algo(PackAlgorithm::Guillotine);
+    packer.set_guillotine_stages(Some(StageLimit::three_stage(true)));
+    packer.set_group_mode(GroupMode::Preferred);
+    packer.add_bin(6, 6);
+    packer.add_rect_to_group(Rectangle { width: 6, height: 6 }, 1);
+    let small = packer.add_rect_to_group(Rectangle { width: 4, height: 1 }, 0);
+    packer.add_rect_to_group(Rectangle { width: 6, height: 4 }, 1);
+    packer.pack();
+    // 分割したグループを詰め直しても、先に配置した矩形は落とさない
+    let ids: Vec<usize> = packer.rect_list().iter().map(|r| r.rect_id).collect();
+    assert_eq!(ids, vec![small]);
+}
+
+#[test]
+fn test_one_stage_cuts_only_full_width_strips() {
+    let rects = vec![
+        Rectangle { width: 10, height: 3 },
+        Rectangle { width: 4, height: 2 },
+        Rectangle { width: 10, height: 5 },
+        Rectangle { width: 4, height: 2 },
+    ];
+    // トリミングなしでは幅がビンと等しい部品だけを横切りで切り出す
+    let placed = staged_guillotine_placement(&rects, 10, 20, false, &StageLimit::one_stage(false));
+    assert_valid(&placed, 10, 20);
+    assert_eq!(placed.len(), 2);
+    assert!(boxes(&placed).iter().all(|&(x, _, w, _)| x == 0 && w == 10));
+
+    // トリミングありでも1本のストリップに部品は1つ
+    let placed = staged_guillotine_placement(&rects, 10, 20, false, &StageLimit::one_stage(true));
+    assert_valid(&placed, 10, 20);
+    assert_eq!(placed.len(), 4);
+    let b = boxes(&placed);
+    assert!(b.iter().all(|&(x, _, _, _)| x == 0));
+    for (i, &(_, ay, _, ah)) in b.iter().enumerate() {
+        for &(_, by, _, bh) in &b[i + 1..] {
+            assert!(ay + ah <= by || by + bh <= ay);
+        }
+    }
+
+    // 0段では切断しないので、ビンと同じサイズの部品だけ
+    let limit = StageLimit { stages: 0, trimming: true };
+    assert_eq!(staged_guillotine_placement(&rects, 10, 5, false, &limit).len(), 1);
+    assert!(staged_guillotine_placement(&rects, 10, 6, false, &limit).is_empty());
+}
@@ -0,0 +1,82 @@
+use rustpack::*;
+
+fn packer_with_items(n: usize) -> Packer {
+    let mut packer = Packer::new();
+    for _ in 0..n {
+        packer.add_rect(Rectangle { width: 5, height: 5 });
+    }
+    packer
+}
+
+#[test]
+fn test_cheaper_stock_chosen() {
+    let mut packer = packer_with_items(4);
+    let large = packer.add_stock(10, 10, 10, 100.0);
+    let small = packer.add_stock(5, 5, 10, 20.0);
+    packer.pack();
+    assert_eq!(packer.rect_list().len(), 4);
+    assert!(packer.bin_list().iter().all(|b| b.stock_id == Some(small)));
+    assert_ne!(large, small);
+    assert_eq!(packer.total_cost(), 80.0);
+}
+
+#[test]
+fn test_quantity_limit_and_lookahead() {
+    let mut packer = packer_with_items(4);
+    let large = packer.add_stock(10, 10, 10, 100.0);
+    packer.add_stock(5, 5, 2, 20.0);
+    packer.pack();
+    // 小さい在庫2枚と大きい在庫1枚(140)より、大きい在庫1枚(100)が安い
+    assert_eq!(packer.total_cost(), 100.0);
+    let bins = packer.bin_list();
+    assert_eq!(bins.len(), 1);
+    assert_eq!(bins[0].stock_id, Some(large));
+}
+
+#[test]
+fn test_owned_bins_used_first() {
+    let mut packer = packer_with_items(3);
+    let owned = packer.add_bin(5, 5);
+    let stock = packer.add_stock(10, 10, 1, 50.0);
+    packer.pack();
+    let bins = packer.bin_list();
+    assert_eq!(bins.len(), 2);
+    assert_eq!((bins[0].bin_id, bins[0].stock_id), (owned, None));
+    assert_eq!(bins[1].stock_id, Some(stock));
+    assert_eq!(packer.total_cost(), 50.0);
+    // 再実行しても在庫のビンは重複しない
+    packer.pack();
+    assert_eq!(packer.bin_list().len(), 2);
+}
+
+#[test]
+fn test_stock_exhausted() {
+    let mut packer = packer_with_items(5);
+    packer.add_stock(10, 5, 2, 10.0);
+    packer.pack();
+    assert_eq!(packer.rect_list().len(), 4);
+    assert_eq!(packer.total_cost(), 20.0);
+}
+
+#[test]
+fn test_bin_ids_stable_across_packs() {
+    let mut packer = Packer::new();
+    packer.set_sort_order(SortOrder::Area);
+    packer.add_bin(10, 10);
+    packer.add_stock(10, 10, 5, 1.0);
+    packer.add_rect(Rectangle { width: 10, height: 10 });
+    packer.add_rect(Rectangle { width: 10, height: 10 });
+    packer.pack();
+    assert_eq!(packer.bin_list().len(), 2);
+
+    // 在庫から取り出したビンの後に追加しても、IDは次のpackでも同じビンを指す
+    let big = packer.add_bin(20, 20);
+    assert_eq!(big, 1);
+    let rect = packer.add_rect(Rectangle { width: 15, height: 15 });
+    packer.pin_to_bin(rect, big);
+    packer.pack();
+    let placed = packer.rect_list().iter().find(|r| r.rect_id == rect).unwrap();
+    assert_eq!(placed.bin_id, big);
+    let bin = packer.bin_list().into_iter().find(|b| b.bin_id == big).unwrap();
+    assert_eq!((bin.width, bin.height), (20, 20));
+}
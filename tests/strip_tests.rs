@@ -0,0 +1,50 @@
+use rustpack::*;
+
+const ALGORITHMS: [PackAlgorithm; 4] = [
+    PackAlgorithm::BottomLeft,
+    PackAlgorithm::MaxRects,
+    PackAlgorithm::Skyline,
+    PackAlgorithm::Guillotine,
+];
+
+#[test]
+fn test_strip_places_everything() {
+    let rects: Vec<Rectangle> = (0..40).map(|i| Rectangle {
+        width: (i % 7) + 3,
+        height: (i % 5) + 2,
+    }).collect();
+    let total_area: u32 = rects.iter().map(|r| r.width * r.height).sum();
+
+    for algo in ALGORITHMS {
+        let layout = strip_pack(&rects, 30, algo, false);
+        assert_eq!(layout.placements.len(), rects.len(), "{:?}", algo);
+        // 使用高さは面積の下限以上、かつ全矩形がその範囲に収まる
+        assert!(layout.height * 30 >= total_area);
+        for &(r, p, rot) in &layout.placements {
+            let (w, h) = if rot { (r.height, r.width) } else { (r.width, r.height) };
+            assert!(p.x + w <= 30);
+            assert!(p.y + h <= layout.height);
+        }
+    }
+}
+
+#[test]
+fn test_strip_height_exact() {
+    let rects = vec![Rectangle { width: 5, height: 4 }; 4];
+    for algo in ALGORITHMS {
+        let layout = strip_pack(&rects, 10, algo, false);
+        assert_eq!(layout.height, 8, "{:?}", algo);
+    }
+}
+
+#[test]
+fn test_strip_rotation_and_too_wide() {
+    let rects = vec![
+        Rectangle { width: 12, height: 3 },
+        Rectangle { width: 20, height: 20 },
+    ];
+    let layout = strip_pack(&rects, 10, PackAlgorithm::Skyline, true);
+    assert_eq!(layout.placements.len(), 1);
+    assert!(layout.placements[0].2);
+    assert_eq!(layout.height, 12);
+}